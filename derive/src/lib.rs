@@ -0,0 +1,116 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt};
+
+/// Derives `align_text::AlignRow`, rendering each field (with its `Display` impl) as a column.
+///
+/// Each field can have an `#[align(...)]` attribute with:
+/// * `left`, `center`, or `right`: where to align it in its column.
+///   If unspecified, right if the column only has numbers, left otherwise.
+/// * `width = N`: the width of its column, the widest cell's if unspecified.
+///
+/// # Example
+/// ```
+/// use align_text::{table, AlignRow};
+///
+/// #[derive(AlignRow)]
+/// struct Score {
+///     name: String,
+///     #[align(width = 6)]
+///     points: u32,
+/// }
+///
+/// let scores = vec![
+///     Score { name: "Ada".to_string(), points: 120 },
+///     Score { name: "Linus".to_string(), points: 7 },
+/// ];
+/// assert_eq!(
+///     table(&scores, true),
+///     vec!["name  points", "Ada      120", "Linus      7"]
+/// );
+/// ```
+#[proc_macro_derive(AlignRow, attributes(align))]
+pub fn derive_align_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "AlignRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns = vec![];
+    let mut headers = vec![];
+    let mut cells = vec![];
+
+    for (index, field) in fields.iter().enumerate() {
+        let mut align = quote!(None);
+        let mut width = quote!(None);
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("align"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("left") {
+                    align = quote!(Some(::align_text::Where::Left));
+                } else if meta.path.is_ident("center") {
+                    align = quote!(Some(::align_text::Where::Center));
+                } else if meta.path.is_ident("right") {
+                    align = quote!(Some(::align_text::Where::Right));
+                } else if meta.path.is_ident("width") {
+                    let num: LitInt = meta.value()?.parse()?;
+                    let num = num.base10_parse::<usize>()?;
+                    width = quote!(Some(#num));
+                } else {
+                    return Err(meta.error("expected left, center, right, or width = N"));
+                }
+                Ok(())
+            })?;
+        }
+        columns.push(quote!((#align, #width)));
+
+        match (&field.ident, fields) {
+            (Some(ident), _) => {
+                headers.push(ident.to_string());
+                cells.push(quote!(self.#ident.to_string()));
+            }
+            (None, Fields::Unnamed(_)) => {
+                let index = syn::Index::from(index);
+                headers.push(index.index.to_string());
+                cells.push(quote!(self.#index.to_string()));
+            }
+            (None, _) => {}
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::align_text::AlignRow for #name #type_generics #where_clause {
+            fn columns() -> ::std::vec::Vec<(::std::option::Option<::align_text::Where>, ::std::option::Option<usize>)> {
+                ::std::vec![#(#columns),*]
+            }
+
+            fn headers() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#headers.to_string()),*]
+            }
+
+            fn cells(&self) -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#cells),*]
+            }
+        }
+    })
+}
@@ -0,0 +1,45 @@
+use align_text::{layout, Options, Overflow, Where};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// A chat log: a few users posting the same reactions and short messages over and over.
+fn chat_log() -> Vec<String> {
+    let messages = [
+        "alice: 👍",
+        "bob: 😂😂😂",
+        "carol: see you tomorrow 👋",
+        "dave: 👩‍💻 on it",
+        "alice: ❤️",
+        "bob: ok",
+    ];
+    (0..10_000)
+        .map(|index| messages[index % messages.len()].to_string())
+        .collect()
+}
+
+/// Plain ASCII lines, all different.
+fn prose() -> Vec<String> {
+    (0..10_000)
+        .map(|index| format!("line {index} of some plain text, with a few words in it"))
+        .collect()
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let options = Options {
+        align: Where::Center,
+        columns: Some(80),
+        overflow: Overflow::Wrap,
+        ..Default::default()
+    };
+
+    let chat = chat_log();
+    c.bench_function("layout chat log", |b| {
+        b.iter(|| layout(black_box(&chat), &options))
+    });
+    let prose = prose();
+    c.bench_function("layout prose", |b| {
+        b.iter(|| layout(black_box(&prose), &options))
+    });
+}
+
+criterion_group!(benches, bench_layout);
+criterion_main!(benches);
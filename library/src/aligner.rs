@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use crate::{width, Bias, Layout, LineLayout, Where};
+
+/// Decides where each line goes within the columns, for alignment rules which [`Where`] doesn't cover.
+/// See [`Layout::realign_with()`] to apply it, and [`Aligners`] to select one by name.
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Bias, LineAligner, Options};
+///
+/// /// Indents each line by 2 more columns than the previous one, as far as it can.
+/// struct Staircase;
+///
+/// impl LineAligner for Staircase {
+///     fn spaces_before(&self, _text: &str, origin: usize, _space: usize, _bias: Bias) -> usize {
+///         origin * 2
+///     }
+/// }
+///
+/// let lines = ["one", "two", "three", "four"].map(String::from);
+/// let options = Options {
+///     columns: Some(8),
+///     ..Default::default()
+/// };
+/// let mut layout = layout(&lines, &options).unwrap();
+/// layout.realign_with(&Staircase, Bias::Left);
+///
+/// assert_eq!(layout.render(false), vec!["one", "  two", "   three", "    four"]);
+/// ```
+pub trait LineAligner: Send + Sync {
+    /// The number of spaces to put before the `text` of a line, out of the `space` free columns around it
+    /// (more than `space` is taken as `space`). `origin` is the index of the input line it comes from,
+    /// and `bias` the side to lean towards when the line can't be placed exactly.
+    fn spaces_before(&self, text: &str, origin: usize, space: usize, bias: Bias) -> usize;
+}
+
+impl LineAligner for Where {
+    fn spaces_before(&self, _text: &str, _origin: usize, space: usize, bias: Bias) -> usize {
+        match self {
+            Where::Left => 0,
+            Where::Center => (space + usize::from(bias)) / 2,
+            Where::Right => space,
+        }
+    }
+}
+
+/// Centers the lines without their trailing punctuation, which hangs on the right,
+/// so that e.g. `Hello!` is centered on `Hello`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpticalCenter;
+
+impl LineAligner for OpticalCenter {
+    fn spaces_before(&self, text: &str, _origin: usize, space: usize, bias: Bias) -> usize {
+        let trimmed = text.trim_end();
+        let hanging =
+            trimmed.trim_end_matches(|ch: char| ch.is_ascii_punctuation() || "…»”’".contains(ch));
+        let overhang = width::display_width(trimmed) - width::display_width(hanging);
+        // the space after the punctuation goes to the right of the text
+        let after = width::display_width(text) - width::display_width(trimmed);
+        ((space + after + overhang + usize::from(bias)) / 2).min(space)
+    }
+}
+
+/// Puts a fraction of the free space before each line, between 0 (left) and 1 (right), e.g. `At(0.3)` puts
+/// 30% of it before the line and 70% after, for layouts whose visual anchor isn't the exact center.
+/// When the spaces can't be split exactly, the bias chooses between rounding down (left) and up (right).
+///
+/// # Example
+/// ```
+/// use align_text::{layout, At, Bias, Options};
+///
+/// let lines = ["title".to_string(), "a longer line".to_string()];
+/// let options = Options {
+///     columns: Some(15),
+///     ..Default::default()
+/// };
+/// let mut layout = layout(&lines, &options).unwrap();
+/// layout.realign_with(&At(0.3), Bias::Left);
+///
+/// assert_eq!(layout.render(false), vec!["   title", "a longer line"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct At(pub f32);
+
+impl LineAligner for At {
+    fn spaces_before(&self, _text: &str, _origin: usize, space: usize, bias: Bias) -> usize {
+        let exact = space as f32 * self.0.clamp(0.0, 1.0);
+        // e.g. 0.3 * 10 isn't exactly 3
+        let nearest = exact.round();
+        let spaces = if (exact - nearest).abs() < 1e-4 {
+            nearest
+        } else {
+            match bias {
+                Bias::Left | Bias::Alternate => exact.floor(),
+                Bias::Right => exact.ceil(),
+            }
+        };
+        spaces as usize
+    }
+}
+
+impl LineLayout {
+    /// Moves the content where `aligner` puts it, keeping the same total of spaces around it.
+    pub fn realign_with(&mut self, aligner: &dyn LineAligner, bias: Bias) {
+        let space = self.before + self.after;
+        self.before = aligner
+            .spaces_before(&self.text, self.origin, space, bias)
+            .min(space);
+        self.after = space - self.before;
+    }
+}
+
+impl Layout {
+    /// Moves the content of each line where `aligner` puts it, within the same columns.
+    pub fn realign_with(&mut self, aligner: &dyn LineAligner, bias: Bias) {
+        let mut odd_lines = 0;
+        for line in self.lines.iter_mut() {
+            let bias = bias.for_line(line.before + line.after, &mut odd_lines);
+            line.realign_with(aligner, bias);
+        }
+    }
+}
+
+/// A set of [`LineAligner`]s selected by name, e.g. from a command line option.
+/// The default one has `left`, `center`, `right`, and `optical-center` ([`OpticalCenter`]),
+/// and more can be registered.
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Aligners, Bias, LineAligner, Options};
+///
+/// /// Puts the lines a third of the way.
+/// struct Third;
+///
+/// impl LineAligner for Third {
+///     fn spaces_before(&self, _text: &str, _origin: usize, space: usize, _bias: Bias) -> usize {
+///         space / 3
+///     }
+/// }
+///
+/// let mut aligners = Aligners::default();
+/// aligners.register("third", Third);
+/// assert_eq!(
+///     aligners.names().collect::<Vec<&str>>(),
+///     vec!["center", "left", "optical-center", "right", "third"]
+/// );
+///
+/// let lines = ["Hello!".to_string()];
+/// let options = Options {
+///     columns: Some(12),
+///     ..Default::default()
+/// };
+/// let mut layout = layout(&lines, &options).unwrap();
+/// layout.realign_with(aligners.get("third").unwrap(), Bias::Left);
+/// assert_eq!(layout.render(false), vec!["  Hello!"]);
+///
+/// layout.realign_with(aligners.get("optical-center").unwrap(), Bias::Left);
+/// assert_eq!(layout.render(false), vec!["   Hello!"]);
+/// ```
+pub struct Aligners {
+    aligners: BTreeMap<String, Box<dyn LineAligner>>,
+}
+
+impl Aligners {
+    /// Adds an aligner, replacing the one with the same name if there's one.
+    pub fn register(&mut self, name: impl Into<String>, aligner: impl LineAligner + 'static) {
+        self.aligners.insert(name.into(), Box::new(aligner));
+    }
+
+    /// The aligner with this name, if there's one.
+    pub fn get(&self, name: &str) -> Option<&dyn LineAligner> {
+        self.aligners.get(name).map(|aligner| aligner.as_ref())
+    }
+
+    /// The names of the aligners, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.aligners.keys().map(String::as_str)
+    }
+}
+
+impl Default for Aligners {
+    fn default() -> Self {
+        let mut aligners = Aligners {
+            aligners: BTreeMap::new(),
+        };
+        aligners.register("left", Where::Left);
+        aligners.register("center", Where::Center);
+        aligners.register("right", Where::Right);
+        aligners.register("optical-center", OpticalCenter);
+        aligners
+    }
+}
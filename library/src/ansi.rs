@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+const ESC: char = '\x1b';
+
+/// The length in bytes of the ANSI escape sequence at the start of the text, if there's one:
+/// a control sequence (`ESC [ ... final`, e.g. colors), an operating system command (`ESC ] ... BEL` or `ESC ] ... ESC \`),
+/// or a two-character escape.
+pub(crate) fn escape_len(text: &str) -> Option<usize> {
+    let rest = text.strip_prefix(ESC)?;
+
+    if let Some(params) = rest.strip_prefix('[') {
+        let end = params.find(|ch: char| ('\x40'..='\x7e').contains(&ch))?;
+        return Some(2 + end + 1);
+    }
+    if let Some(command) = rest.strip_prefix(']') {
+        let end = command
+            .char_indices()
+            .find_map(|(index, ch)| match ch {
+                '\x07' => Some(index + 1),
+                ESC if command[index + 1..].starts_with('\\') => Some(index + 2),
+                _ => None,
+            })
+            .unwrap_or(command.len());
+        return Some(2 + end);
+    }
+    rest.chars().next().map(|ch| 1 + ch.len_utf8())
+}
+
+/// The text without its escape sequences.
+pub(crate) fn strip(text: &str) -> Cow<'_, str> {
+    if !text.contains(ESC) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut visible = String::with_capacity(text.len());
+    let mut index = 0;
+    while let Some(ch) = text[index..].chars().next() {
+        match escape_len(&text[index..]) {
+            Some(len) => index += len,
+            None => {
+                visible.push(ch);
+                index += ch.len_utf8();
+            }
+        }
+    }
+    Cow::Owned(visible)
+}
+
+/// Closes an OSC 8 hyperlink.
+const LINK_END: &str = "\x1b]8;;\x1b\\";
+
+/// The styles (SGR sequences) and the hyperlink (OSC 8 sequence) in effect at some point of a line,
+/// to carry them over when it's split.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Styles {
+    sgr: Vec<String>,
+    link: Option<String>,
+}
+
+impl Styles {
+    /// Takes the escape sequences of the text into account.
+    pub(crate) fn update(&mut self, text: &str) {
+        let mut index = 0;
+        while let Some(ch) = text[index..].chars().next() {
+            let Some(len) = escape_len(&text[index..]) else {
+                index += ch.len_utf8();
+                continue;
+            };
+
+            let sequence = &text[index..index + len];
+            if let Some(params) = sequence
+                .strip_prefix("\x1b[")
+                .and_then(|sequence| sequence.strip_suffix('m'))
+            {
+                if params.is_empty() || params == "0" {
+                    self.sgr.clear();
+                } else {
+                    self.sgr.push(sequence.to_string());
+                }
+            } else if let Some(link) = sequence.strip_prefix("\x1b]8;") {
+                // `params;URI`, an empty URI closing the link
+                let uri = link
+                    .split_once(';')
+                    .map_or("", |(_, uri)| uri.trim_end_matches(['\x07', '\\', '\x1b']));
+                self.link = (!uri.is_empty()).then(|| sequence.to_string());
+            }
+            index += len;
+        }
+    }
+
+    /// The sequences which restore the styles and the hyperlink at the start of a piece.
+    pub(crate) fn opening(&self) -> String {
+        self.sgr.concat() + self.link.as_deref().unwrap_or_default()
+    }
+
+    /// The sequences which close the hyperlink and reset the styles at the end of a piece, so they don't bleed past it.
+    pub(crate) fn closing(&self) -> String {
+        let mut closing = String::new();
+        if self.link.is_some() {
+            closing.push_str(LINK_END);
+        }
+        if !self.sgr.is_empty() {
+            closing.push_str("\x1b[0m");
+        }
+        closing
+    }
+}
@@ -0,0 +1,65 @@
+use crate::width;
+
+/// Aligns the line-continuation backslashes at the end of the lines (in shell scripts, Makefiles, C macros, ...)
+/// to the same column: `column` (counted from 0) if given, one space after the longest line of each block otherwise.
+/// A block is a run of consecutive lines ending with a backslash.
+///
+/// Lines which are too long for the column get a single space before their backslash.
+///
+/// # Example
+/// ```
+/// use align_text::align_backslashes;
+///
+/// let lines = ["cargo build \\", "    --release \\", "    --workspace", "echo done"].map(String::from);
+///
+/// assert_eq!(
+///     align_backslashes(&lines, None),
+///     vec!["cargo build   \\", "    --release \\", "    --workspace", "echo done"]
+/// );
+/// assert_eq!(align_backslashes(&lines, Some(16))[0], "cargo build     \\");
+/// ```
+pub fn align_backslashes(lines: &[String], column: Option<usize>) -> Vec<String> {
+    // the content before the backslash
+    let contents = lines
+        .iter()
+        .map(|line| {
+            let content = line.trim_end().strip_suffix('\\')?;
+            Some(content.trim_end())
+        })
+        .collect::<Vec<Option<&str>>>();
+
+    let mut result = Vec::with_capacity(lines.len());
+    let mut start = 0;
+    while start < lines.len() {
+        let end = match contents[start] {
+            None => start + 1,
+            Some(_) => contents[start..]
+                .iter()
+                .position(Option::is_none)
+                .map_or(lines.len(), |length| start + length),
+        };
+
+        let column = column.unwrap_or_else(|| {
+            contents[start..end]
+                .iter()
+                .flatten()
+                .map(|content| width::display_width(content) + 1)
+                .max()
+                .unwrap_or(0)
+        });
+
+        for index in start..end {
+            result.push(match contents[index] {
+                None => lines[index].clone(),
+                Some(content) => {
+                    let padding = column.saturating_sub(width::display_width(content)).max(1);
+                    format!("{content}{}\\", " ".repeat(padding))
+                }
+            });
+        }
+
+        start = end;
+    }
+
+    result
+}
@@ -0,0 +1,318 @@
+use std::fmt::Display;
+
+use crate::{layout, Bias, Error, Layout, LineLayout, Options};
+
+/// Aligned lines which remember how they were aligned: the original lines, the options, and the [`Layout`].
+/// Unlike the output of [`Align::align_text()`](crate::Align::align_text), it can be aligned again with other options,
+/// without trimming the lines again.
+///
+/// # Example
+/// ```
+/// use align_text::{AlignedBlock, Options, Where};
+///
+/// let lines = vec!["  Hello ".to_string(), "World!".to_string()];
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(8),
+///     trim: true,
+///     ..Default::default()
+/// };
+///
+/// let mut block = AlignedBlock::new(lines, options.clone()).unwrap();
+/// assert_eq!((block.width(), block.height()), (8, 2));
+/// assert_eq!(block.lines(), vec!["   Hello", "  World!"]);
+///
+/// block
+///     .realign(Options {
+///         align: Where::Center,
+///         ..options
+///     })
+///     .unwrap();
+/// assert_eq!(String::from(block), " Hello\n World!");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlignedBlock {
+    original: Vec<String>,
+    /// The trimmed lines, once they're needed.
+    trimmed: Option<Vec<String>>,
+    options: Options,
+    layout: Layout,
+}
+
+impl AlignedBlock {
+    /// Aligns the lines with the options, see [`layout()`].
+    pub fn new(lines: Vec<String>, options: Options) -> Result<AlignedBlock, Error> {
+        let mut block = AlignedBlock {
+            original: lines,
+            trimmed: None,
+            options: Options::default(),
+            layout: Layout::default(),
+        };
+        block.realign(options)?;
+
+        Ok(block)
+    }
+
+    /// Aligns the lines again with other options.
+    /// On error, the block is left as it was.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{AlignedBlock, Options, Where};
+    ///
+    /// let mut block = AlignedBlock::new(vec!["  a ".to_string()], Options::default()).unwrap();
+    /// let options = Options {
+    ///     align: Where::Right,
+    ///     columns: Some(3),
+    ///     trim: true,
+    ///     rows: Some(3),
+    ///     ..Default::default()
+    /// };
+    /// block.realign(options.clone()).unwrap();
+    /// assert_eq!(block.lines(), vec!["  a", "", ""]);
+    /// // the blank lines added around the text show none of it
+    /// let ranges = block.layout().lines.iter().map(|line| line.range.clone()).collect::<Vec<_>>();
+    /// assert_eq!(ranges, vec![2..3, 0..0, 0..0]);
+    ///
+    /// let block = AlignedBlock::new(vec![], options).unwrap();
+    /// assert_eq!(block.lines(), vec!["", "", ""]);
+    /// ```
+    pub fn realign(&mut self, options: Options) -> Result<(), Error> {
+        // the tabs are expanded and the lines dedented before trimming
+        let layout = if options.trim && options.tab_width.is_none() && !options.dedent {
+            let trimmed = self.trimmed.get_or_insert_with(|| {
+                self.original
+                    .iter()
+                    .map(|line| line.trim().to_string())
+                    .collect()
+            });
+            let untrimmed = Options {
+                trim: false,
+                ..options.clone()
+            };
+            let mut layout = layout(trimmed, &untrimmed)?;
+            // the ranges are in the original lines, like when they're trimmed by layout(),
+            // but for the blank lines added around them
+            for line in layout.lines.iter_mut() {
+                let Some(original) = self.original.get(line.origin) else {
+                    continue;
+                };
+                if line.range.is_empty() {
+                    continue;
+                }
+                let start = original.len() - original.trim_start().len();
+                line.range = line.range.start + start..line.range.end + start;
+            }
+            layout
+        } else {
+            layout(&self.original, &options)?
+        };
+
+        self.layout = layout;
+        self.options = options;
+        Ok(())
+    }
+
+    /// Replaces some of the lines, given with their index in the original lines, and aligns the block again,
+    /// for editors to keep it aligned as it's edited. Returns the indices of the aligned lines to rewrite:
+    /// those of the changed lines if only they were padded again, or all of them (up to the greater height)
+    /// if the whole block was aligned again.
+    ///
+    /// Only the changed lines are padded again when the number of columns stays the same:
+    /// it's given, or the changed lines don't get wider and weren't the only widest ones.
+    /// The whole block is aligned again otherwise, or when lines wrap or are placed vertically.
+    /// On error, the block is left as it was.
+    ///
+    /// # Panics
+    /// If an index is out of the original lines.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{AlignedBlock, Bias, Options, Where};
+    ///
+    /// let lines = vec!["a".to_string(), "longest".to_string(), "bc".to_string()];
+    /// let options = Options {
+    ///     align: Where::Right,
+    ///     ..Default::default()
+    /// };
+    /// let mut block = AlignedBlock::new(lines, options).unwrap();
+    ///
+    /// assert_eq!(block.update(&[(2, "def".to_string())]).unwrap(), vec![2]);
+    /// assert_eq!(block.lines(), vec!["      a", "longest", "    def"]);
+    ///
+    /// // the widest line got shorter, so the others move too
+    /// assert_eq!(block.update(&[(1, "long".to_string())]).unwrap(), vec![0, 1, 2]);
+    /// assert_eq!(block.lines(), vec!["   a", "long", " def"]);
+    ///
+    /// // the same as aligning the new lines from scratch, with any bias
+    /// let lines = vec!["ab".to_string(), "cd".to_string(), "longest".to_string()];
+    /// let options = Options {
+    ///     align: Where::Center,
+    ///     bias: Bias::Alternate,
+    ///     ..Default::default()
+    /// };
+    /// let mut block = AlignedBlock::new(lines, options.clone()).unwrap();
+    /// block.update(&[(1, "xy".to_string())]).unwrap();
+    /// let fresh = AlignedBlock::new(block.original().to_vec(), options).unwrap();
+    /// assert_eq!(block.lines(), fresh.lines());
+    /// assert_eq!(block.lines(), vec!["  ab", "   xy", "longest"]);
+    ///
+    /// // the ranges are in the original lines, whether the block was aligned again or not
+    /// let lines = vec!["  ab ".to_string(), "longest".to_string()];
+    /// let options = Options {
+    ///     align: Where::Right,
+    ///     trim: true,
+    ///     ..Default::default()
+    /// };
+    /// let mut block = AlignedBlock::new(lines, options).unwrap();
+    /// assert_eq!(block.layout().lines[0].range, 2..4);
+    /// assert_eq!(block.update(&[(0, "   cd".to_string())]).unwrap(), vec![0]);
+    /// assert_eq!(block.layout().lines[0].range, 3..5);
+    /// assert_eq!(block.lines(), vec!["     cd", "longest"]);
+    /// ```
+    pub fn update(&mut self, changes: &[(usize, String)]) -> Result<Vec<usize>, Error> {
+        if let Some(positions) = self.update_lines(changes) {
+            return Ok(positions);
+        }
+
+        let height = self.layout.lines.len();
+        let previous = changes
+            .iter()
+            .map(|(index, _)| (*index, self.original[*index].clone()))
+            .rev()
+            .collect::<Vec<(usize, String)>>();
+        self.replace(changes);
+        if let Err(e) = self.realign(self.options.clone()) {
+            // in reverse, for the lines changed several times
+            self.replace(&previous);
+            return Err(e);
+        }
+        Ok((0..height.max(self.layout.lines.len())).collect())
+    }
+
+    /// Pads the changed lines again, if the rest of the block doesn't need to change.
+    /// Returns the indices of the aligned lines it changed, `None` if it didn't.
+    fn update_lines(&mut self, changes: &[(usize, String)]) -> Option<Vec<usize>> {
+        let options = &self.options;
+        // the alternate bias depends on the lines before
+        if options.rows.is_some()
+            || options.bias == Bias::Alternate
+            || options.squeeze
+            || options.shrink
+            || options.tab_width.is_some()
+            || options.dedent
+            || options.remainder.is_some()
+            || options.last_line.is_some()
+        {
+            return None;
+        }
+
+        // the aligned line of each original line, if it didn't wrap
+        let mut counts = vec![0; self.original.len()];
+        let mut positions = vec![0; self.original.len()];
+        for (position, line) in self.layout.lines.iter().enumerate() {
+            counts[line.origin] += 1;
+            positions[line.origin] = position;
+        }
+
+        let columns = self.layout.columns;
+        let mut widest = false;
+        for (index, text) in changes {
+            let width = options.width_of(if options.trim { text.trim() } else { text });
+            if counts[*index] != 1 || width > columns {
+                return None;
+            }
+            widest |= width == columns;
+        }
+
+        // the text's width stays the same if another line still has it
+        if options.columns.is_none() && !widest {
+            let changed = changes
+                .iter()
+                .map(|(index, _)| *index)
+                .collect::<Vec<usize>>();
+            let kept = self
+                .layout
+                .lines
+                .iter()
+                .any(|line| line.before + line.after == 0 && !changed.contains(&line.origin));
+            if !kept {
+                return None;
+            }
+        }
+
+        self.replace(changes);
+        let options = &self.options;
+        let mut changed = vec![];
+        for (index, text) in changes {
+            let (start, text) = match options.trim {
+                true => (text.len() - text.trim_start().len(), text.trim()),
+                false => (0, text.as_str()),
+            };
+            let range = start..start + text.len();
+            self.layout.lines[positions[*index]] =
+                LineLayout::padded(text.to_string(), *index, range, columns, options);
+            changed.push(positions[*index]);
+        }
+        changed.sort_unstable();
+        changed.dedup();
+        Some(changed)
+    }
+
+    /// Replaces the lines, and their trimmed versions if there are some.
+    fn replace(&mut self, changes: &[(usize, String)]) {
+        for (index, text) in changes {
+            self.original[*index] = text.clone();
+            if let Some(trimmed) = &mut self.trimmed {
+                trimmed[*index] = text.trim().to_string();
+            }
+        }
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.layout.columns
+    }
+
+    /// The number of lines, after wrapping.
+    pub fn height(&self) -> usize {
+        self.layout.lines.len()
+    }
+
+    /// The lines as they were given.
+    pub fn original(&self) -> &[String] {
+        &self.original
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// The aligned lines.
+    pub fn lines(&self) -> Vec<String> {
+        self.layout.render(self.options.keep_spaces)
+    }
+}
+
+impl From<AlignedBlock> for Vec<String> {
+    fn from(block: AlignedBlock) -> Self {
+        block.lines()
+    }
+}
+
+impl From<AlignedBlock> for String {
+    /// The aligned lines, joined with `\n`.
+    fn from(block: AlignedBlock) -> Self {
+        block.lines().join("\n")
+    }
+}
+
+impl Display for AlignedBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt::Display;
+
+use crate::emoji::cluster_len;
+use crate::{ansi, width};
+
+/// A non-fatal issue with the text, which may make the result look wrong, to report to the users.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// A hard tab, which is counted as 1 column when measuring, but takes a terminal-dependent number when displayed.
+    Tab { line_index: usize, column: usize },
+    /// A control character (other than a tab or an escape sequence), which is counted as 1 column when measuring,
+    /// but may take none when displayed.
+    ControlCharacter {
+        line_index: usize,
+        column: usize,
+        character: char,
+    },
+    /// A value which was cut to fit in `available` columns, see [`RecordLayout`](crate::RecordLayout).
+    Truncated {
+        line_index: usize,
+        width: usize,
+        available: usize,
+    },
+}
+
+impl Diagnostic {
+    /// The index of the line it's about.
+    pub fn line_index(&self) -> usize {
+        match self {
+            Diagnostic::Tab { line_index, .. }
+            | Diagnostic::ControlCharacter { line_index, .. }
+            | Diagnostic::Truncated { line_index, .. } => *line_index,
+        }
+    }
+
+    /// Changes the index of the line, e.g. to the index in the whole text when only a part of it was checked.
+    pub fn map_line_index(self, f: impl FnOnce(usize) -> usize) -> Diagnostic {
+        match self {
+            Diagnostic::Tab { line_index, column } => Diagnostic::Tab {
+                line_index: f(line_index),
+                column,
+            },
+            Diagnostic::ControlCharacter {
+                line_index,
+                column,
+                character,
+            } => Diagnostic::ControlCharacter {
+                line_index: f(line_index),
+                column,
+                character,
+            },
+            Diagnostic::Truncated {
+                line_index,
+                width,
+                available,
+            } => Diagnostic::Truncated {
+                line_index: f(line_index),
+                width,
+                available,
+            },
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::Tab { line_index, column } => write!(
+                f,
+                "line {} column {column}: hard tab, its width depends on the terminal",
+                line_index + 1
+            ),
+            Diagnostic::ControlCharacter {
+                line_index,
+                column,
+                character,
+            } => write!(
+                f,
+                "line {} column {column}: control character {}, it's counted as 1 column",
+                line_index + 1,
+                character.escape_unicode()
+            ),
+            Diagnostic::Truncated {
+                line_index,
+                width,
+                available,
+            } => write!(
+                f,
+                "line {}: a value {width} wide was cut to {available} columns",
+                line_index + 1
+            ),
+        }
+    }
+}
+
+/// Finds the characters in the lines which throw off their widths: hard tabs and control characters
+/// (the escape sequences aside). The columns start at 1, and are display columns.
+///
+/// # Example
+/// ```
+/// use align_text::{diagnose, Diagnostic};
+///
+/// let lines = vec!["name\tvalue".to_string(), "\x1b[1mbold\x1b[0m\x08".to_string()];
+///
+/// assert_eq!(
+///     diagnose(&lines),
+///     vec![
+///         Diagnostic::Tab { line_index: 0, column: 5 },
+///         Diagnostic::ControlCharacter { line_index: 1, column: 5, character: '\x08' },
+///     ]
+/// );
+/// ```
+pub fn diagnose(lines: &[String]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    for (line_index, line) in lines.iter().enumerate() {
+        let mut index = 0;
+        let mut column = 1;
+        while let Some(character) = line[index..].chars().next() {
+            if let Some(len) = ansi::escape_len(&line[index..]) {
+                index += len;
+                continue;
+            }
+
+            if character == '\t' {
+                diagnostics.push(Diagnostic::Tab { line_index, column });
+            } else if character.is_control() {
+                diagnostics.push(Diagnostic::ControlCharacter {
+                    line_index,
+                    column,
+                    character,
+                });
+            }
+            let len = cluster_len(&line[index..]);
+            column += width::display_width(&line[index..index + len]);
+            index += len;
+        }
+    }
+
+    diagnostics
+}
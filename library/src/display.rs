@@ -0,0 +1,44 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{width, Options, Where};
+
+/// Wraps a text to format it according to the width, fill, and alignment of a format spec (`{:^30}`, `{:*>10}`, ...),
+/// measuring it in terminal columns like the rest of the crate (see [`display_width()`](crate::display_width)),
+/// instead of counting its `char`s.
+///
+/// # Example
+/// ```
+/// use align_text::Aligned;
+///
+/// assert_eq!(format!("[{:^8}]", Aligned("日本")), "[  日本  ]");
+/// assert_eq!(format!("[{:*>4}]", Aligned("e\u{301}")), "[***e\u{301}]");
+/// assert_eq!(format!("[{:4}]", Aligned("ab")), "[ab  ]");
+/// assert_eq!(format!("[{}]", Aligned("ab")), "[ab]");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aligned<T>(pub T);
+
+impl<T: AsRef<str>> Display for Aligned<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = self.0.as_ref();
+        let options = Options::from_formatter(f);
+        let Some(columns) = options.columns else {
+            return f.write_str(text);
+        };
+
+        let space = columns.saturating_sub(width::display_width(text));
+        let before = match options.align {
+            Where::Left => 0,
+            Where::Center => (space + usize::from(options.bias)) / 2,
+            Where::Right => space,
+        };
+        let fill = f.fill().to_string();
+
+        write!(
+            f,
+            "{}{text}{}",
+            fill.repeat(before),
+            fill.repeat(space - before)
+        )
+    }
+}
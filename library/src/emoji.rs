@@ -0,0 +1,87 @@
+use unicode_width::UnicodeWidthStr;
+
+/// How many columns the emoji take up, which varies between terminals,
+/// especially for ZWJ sequences (e.g. 👩‍💻) and symbols followed by a variation selector (e.g. ❤️).
+/// It should match the terminal the text is shown in, for the centering of emoji-heavy text to look right,
+/// see [`Options::emoji_width`](crate::Options::emoji_width).
+///
+/// # Example
+/// ```
+/// use align_text::{EmojiWidth, Options};
+///
+/// let options = |emoji_width| Options {
+///     emoji_width,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(options(EmojiWidth::Narrow).width_of("hi 👩‍💻"), 4);
+/// assert_eq!(options(EmojiWidth::Wide).width_of("hi 👩‍💻"), 5);
+/// assert_eq!(options(EmojiWidth::Wide).width_of("❤️"), 2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmojiWidth {
+    /// As given by the Unicode standard.
+    #[default]
+    Unicode,
+    /// Each emoji sequence takes 1 column.
+    Narrow,
+    /// Each emoji sequence takes 2 columns.
+    Wide,
+}
+
+const ZWJ: char = '\u{200d}';
+
+/// Whether the character continues the emoji sequence before it:
+/// a joiner, a variation selector, a skin tone, a keycap, or a tag.
+fn is_continuation(ch: char) -> bool {
+    matches!(ch, ZWJ | '\u{fe0e}' | '\u{fe0f}' | '\u{20e3}' | '\u{1f3fb}'..='\u{1f3ff}' | '\u{e0020}'..='\u{e007f}')
+}
+
+fn is_regional_indicator(ch: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&ch)
+}
+
+/// The length in bytes of the character at the start of the text, along with the characters making an emoji sequence with it.
+pub(crate) fn cluster_len(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    let Some((_, first)) = chars.next() else {
+        return 0;
+    };
+
+    let mut end = first.len_utf8();
+    let mut joined = false;
+    // a flag is a pair of regional indicators
+    if is_regional_indicator(first) {
+        if let Some(&(index, ch)) = chars.peek() {
+            if is_regional_indicator(ch) {
+                chars.next();
+                end = index + ch.len_utf8();
+            }
+        }
+    }
+    for (index, ch) in chars {
+        if !joined && !is_continuation(ch) {
+            break;
+        }
+        joined = ch == ZWJ;
+        end = index + ch.len_utf8();
+    }
+
+    end
+}
+
+/// The number of columns of a character and the characters making an emoji sequence with it, see [`cluster_len()`],
+/// the emoji taking up `emoji_width` columns.
+pub(crate) fn cluster_width(cluster: &str, emoji_width: EmojiWidth) -> usize {
+    let width = cluster.width();
+    let emoji = cluster.chars().skip(1).any(is_continuation)
+        || cluster.chars().next().is_some_and(|ch| {
+            is_regional_indicator(ch) || (width == 2 && ('\u{1f000}'..='\u{1faff}').contains(&ch))
+        });
+
+    match emoji_width {
+        EmojiWidth::Narrow if emoji => 1,
+        EmojiWidth::Wide if emoji => 2,
+        _ => width,
+    }
+}
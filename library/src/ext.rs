@@ -0,0 +1,52 @@
+use crate::width::{self, Metric};
+use crate::Where;
+
+/// Helpers to align a single string, measuring it like the rest of the crate
+/// (see [`display_width()`](crate::display_width)).
+///
+/// # Example
+/// ```
+/// use align_text::{AlignStr, Where};
+///
+/// assert_eq!("total".pad_to(8, Where::Right, '.'), "...total");
+/// assert_eq!("日本".center(6), " 日本 ");
+/// assert_eq!("a long title".truncate_display(8, "…"), "a long …");
+/// assert_eq!("short".truncate_display(8, "…"), "short");
+/// ```
+pub trait AlignStr {
+    /// Pads the text with `fill` up to `width` columns, placing it according to `align`
+    /// (biased to the left when it can't be perfectly centered). Text which is already wider is returned as is.
+    fn pad_to(&self, width: usize, align: Where, fill: char) -> String;
+
+    /// Centers the text within `width` columns with spaces, see [`AlignStr::pad_to()`].
+    fn center(&self, width: usize) -> String;
+
+    /// Cuts the text to fit in `width` columns, ending it with `ellipsis` when it's cut.
+    /// Characters and emoji sequences aren't split, and the styles open at the cut are closed.
+    fn truncate_display(&self, width: usize, ellipsis: &str) -> String;
+}
+
+impl AlignStr for str {
+    fn pad_to(&self, width: usize, align: Where, fill: char) -> String {
+        let space = width.saturating_sub(width::display_width(self));
+        let before = match align {
+            Where::Left => 0,
+            Where::Center => space / 2,
+            Where::Right => space,
+        };
+        let fill = fill.to_string();
+        format!(
+            "{}{self}{}",
+            fill.repeat(before),
+            fill.repeat(space - before)
+        )
+    }
+
+    fn center(&self, width: usize) -> String {
+        self.pad_to(width, Where::Center, ' ')
+    }
+
+    fn truncate_display(&self, width: usize, ellipsis: &str) -> String {
+        width::truncate(self, width, ellipsis, Metric::default())
+    }
+}
@@ -0,0 +1,109 @@
+use std::num::NonZeroUsize;
+
+use crate::{layout, Error, Layout, LineLayout, Options};
+
+/// Flows the lines into side-by-side columns, like a newspaper: down the first column, then the next, and so on.
+/// Each column is aligned according to `options`, within `options.columns` (the text's width if `None`).
+///
+/// # Params
+/// * `count`: Number of columns side by side.
+/// * `rows`: Number of lines in each column, `None` to spread the lines evenly over the columns.
+///   When there are more lines than the columns can hold, the next ones go into another set of columns below.
+/// * `gutter`: Number of spaces between the columns.
+///
+/// `options.rows` is ignored. With `options.keep_spaces`, all the lines have the same width.
+///
+/// # Example
+/// ```
+/// use std::num::NonZeroUsize;
+/// use align_text::{flow_columns, Options, Overflow, Where};
+///
+/// let lines = ["one", "two", "three", "four", "five"].map(String::from);
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(5),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     flow_columns(&lines, NonZeroUsize::new(2).unwrap(), None, 3, &options).unwrap(),
+///     vec!["  one    four", "  two    five", "three"]
+/// );
+/// ```
+pub fn flow_columns(
+    lines: &[String],
+    count: NonZeroUsize,
+    rows: Option<usize>,
+    gutter: usize,
+    options: &Options,
+) -> Result<Vec<String>, Error> {
+    Ok(flow_layout(lines, count, rows, gutter, options)?.into_rendered(false))
+}
+
+/// Like [`flow_columns()`], but returns the layout of the flowed lines, e.g. to follow the input lines:
+/// each one takes the origin and the [`LineLayout::range`] of the line it starts with (in the first column).
+///
+/// # Example
+/// ```
+/// use std::num::NonZeroUsize;
+/// use align_text::{flow_layout, Options};
+///
+/// let lines = ["one", "two", "three"].map(String::from);
+/// let count = NonZeroUsize::new(2).unwrap();
+/// let layout = flow_layout(&lines, count, None, 1, &Options::default()).unwrap();
+///
+/// assert_eq!(layout.render(false), vec!["one   three", "two"]);
+/// assert_eq!(layout.lines.iter().map(|line| line.origin).collect::<Vec<usize>>(), vec![0, 1]);
+/// ```
+pub fn flow_layout(
+    lines: &[String],
+    count: NonZeroUsize,
+    rows: Option<usize>,
+    gutter: usize,
+    options: &Options,
+) -> Result<Layout, Error> {
+    let column_options = Options {
+        keep_spaces: true,
+        rows: None,
+        ..options.clone()
+    };
+    // padded to the same width
+    let aligned = layout(lines, &column_options)?;
+    let blank = " ".repeat(aligned.columns);
+    let rendered = aligned.render(true);
+
+    let count = count.get();
+    let rows = rows
+        .unwrap_or_else(|| rendered.len().div_ceil(count))
+        .max(1);
+
+    let mut result = Layout::default();
+    for (page, starts) in rendered
+        .chunks(rows * count)
+        .zip(aligned.lines.chunks(rows * count))
+    {
+        let columns = page.chunks(rows).collect::<Vec<&[String]>>();
+        for (row, start) in starts.iter().take(columns[0].len()).enumerate() {
+            let line = columns
+                .iter()
+                .map(|column| column.get(row).unwrap_or(&blank).as_str())
+                .collect::<Vec<&str>>()
+                .join(&" ".repeat(gutter));
+            let text = match options.keep_spaces {
+                true => line,
+                false => line.trim_end().to_string(),
+            };
+
+            result.columns = result.columns.max(options.width_of(&text));
+            result.lines.push(LineLayout {
+                text,
+                origin: start.origin,
+                range: start.range.clone(),
+                before: 0,
+                after: 0,
+            });
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,296 @@
+use std::str::FromStr;
+
+use crate::{
+    width::{pad, Metric},
+    Bias, EmojiWidth, Where, WidthUnit,
+};
+
+/// A part of a [`LineFormat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    /// Text written as is.
+    Literal(String),
+    /// The next whitespace-separated field of the line, padded to `width` columns.
+    /// Without an alignment, it's aligned right if the column only has numbers, left otherwise (see [`LineFormat::auto_numeric`]).
+    Column { align: Option<Where>, width: usize },
+}
+
+/// A printf-like format applied to each line, e.g. `{:<20} {:^10} {:>8}`.
+///
+/// Each `{}` takes the next whitespace-separated field of the line.
+/// It can specify a width and an alignment: `<` left, `^` center, `>` right.
+/// Without an alignment, the columns which only contain numbers are aligned right, and the others left.
+/// If a line has more fields than the format, the extra ones are added to the last `{}`.
+/// Use `{{` and `}}` for literal braces.
+///
+/// # Example
+/// ```
+/// use align_text::{Bias, LineFormat};
+///
+/// let format: LineFormat = "{:<6}|{:^5}|{:>4}".parse().unwrap();
+/// let lines = vec!["apple 3 1.50".to_string(), "kiwi 12 0.25".to_string()];
+///
+/// assert_eq!(
+///     format.format(&lines, Bias::Left),
+///     vec!["apple |  3  |1.50", "kiwi  | 12  |0.25"]
+/// );
+///
+/// let mut format: LineFormat = "{:6}|{:3}|{:5}".parse().unwrap();
+/// assert_eq!(
+///     format.format(&lines, Bias::Left),
+///     vec!["apple |  3| 1.50", "kiwi  | 12| 0.25"]
+/// );
+///
+/// format.auto_numeric = false;
+/// assert_eq!(
+///     format.format(&lines, Bias::Left),
+///     vec!["apple |3  |1.50 ", "kiwi  |12 |0.25 "]
+/// );
+///
+/// assert!("{:<5".parse::<LineFormat>().is_err());
+/// ```
+///
+/// Numbers, including amounts with a currency symbol and accounting negatives, are aligned on their decimal separator:
+/// ```
+/// use align_text::{Bias, LineFormat};
+///
+/// let mut format: LineFormat = "{:<5} {:12}".parse().unwrap();
+/// format.decimal_separator = ',';
+/// let lines = vec!["rent (1.200,50)".to_string(), "fees €35".to_string(), "tips 7,5".to_string()];
+///
+/// assert_eq!(
+///     format.format(&lines, Bias::Left),
+///     vec!["rent    (1.200,50)", "fees       €35    ", "tips         7,5  "]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineFormat {
+    pub fields: Vec<Field>,
+    /// Whether the columns without an alignment are aligned right when they only contain numbers (the default),
+    /// instead of left. The numbers are aligned on their [`LineFormat::decimal_separator`].
+    pub auto_numeric: bool,
+    /// The character separating the integer part of the numbers from their decimals, `.` by default,
+    /// the other separators (`,`, `.`, `_`, `'`) being taken as thousands separators.
+    pub decimal_separator: char,
+    /// How wide the emoji are, see [`Options::emoji_width`](crate::Options::emoji_width).
+    pub emoji_width: EmojiWidth,
+    /// What the widths are counted in, see [`Options::width_unit`](crate::Options::width_unit).
+    pub width_unit: WidthUnit,
+}
+
+impl FromStr for LineFormat {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut fields = vec![];
+        let mut literal = String::new();
+        let mut chars = input.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => spec.push(ch),
+                            None => return Err(format!("unterminated '{{{spec}' in format")),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        fields.push(Field::Literal(std::mem::take(&mut literal)));
+                    }
+                    fields.push(parse_column(&spec)?);
+                }
+                '}' => return Err("unmatched '}' in format".to_string()),
+                _ => literal.push(ch),
+            }
+        }
+
+        if !literal.is_empty() {
+            fields.push(Field::Literal(literal));
+        }
+
+        Ok(LineFormat {
+            fields,
+            auto_numeric: true,
+            decimal_separator: '.',
+            emoji_width: EmojiWidth::default(),
+            width_unit: WidthUnit::default(),
+        })
+    }
+}
+
+/// Parses the inside of a `{...}`: empty, or `:` followed by an optional alignment and a width.
+fn parse_column(spec: &str) -> Result<Field, String> {
+    let invalid = || format!("invalid format field '{{{spec}}}'");
+
+    let spec = match spec.strip_prefix(':') {
+        Some(spec) => spec,
+        None if spec.is_empty() => spec,
+        None => return Err(invalid()),
+    };
+
+    let (align, width) = match spec.chars().next() {
+        Some('<') => (Some(Where::Left), &spec[1..]),
+        Some('^') => (Some(Where::Center), &spec[1..]),
+        Some('>') => (Some(Where::Right), &spec[1..]),
+        _ => (None, spec),
+    };
+
+    let width = match width {
+        "" => 0,
+        width => width.parse().map_err(|_| invalid())?,
+    };
+
+    Ok(Field::Column { align, width })
+}
+
+impl LineFormat {
+    /// Formats one line, the columns without an alignment are aligned according to its own values.
+    /// `bias`: Which side to bias towards if a field can't be perfectly centered.
+    pub fn format_line(&self, line: &str, bias: Bias) -> String {
+        let values = self.values(line);
+        let numeric = values
+            .iter()
+            .map(|value| is_number(value).then(|| self.decimals_width(value)))
+            .collect::<Vec<Option<usize>>>();
+        self.format_values(&values, &numeric, bias)
+    }
+
+    /// Formats each line, the columns without an alignment are aligned according to the values of all the lines.
+    /// The numbers of a column are also aligned on their decimal separator.
+    /// See [`LineFormat::format_line()`].
+    pub fn format(&self, lines: &[String], bias: Bias) -> Vec<String> {
+        let values = lines
+            .iter()
+            .map(|line| self.values(line))
+            .collect::<Vec<Vec<String>>>();
+
+        // the widest decimals of each column which only has numbers (ignoring the missing values)
+        let num_columns = values.first().map_or(0, Vec::len);
+        let numeric = (0..num_columns)
+            .map(|column| {
+                let column_values = values
+                    .iter()
+                    .map(|values| &values[column])
+                    .filter(|value| !value.is_empty())
+                    .collect::<Vec<&String>>();
+                if column_values.is_empty() || !column_values.iter().all(|value| is_number(value)) {
+                    return None;
+                }
+                column_values
+                    .iter()
+                    .map(|value| self.decimals_width(value))
+                    .max()
+            })
+            .collect::<Vec<Option<usize>>>();
+
+        values
+            .iter()
+            .map(|values| self.format_values(values, &numeric, bias))
+            .collect()
+    }
+
+    /// The value of each column in the line.
+    fn values(&self, line: &str) -> Vec<String> {
+        let num_columns = self
+            .fields
+            .iter()
+            .filter(|field| matches!(field, Field::Column { .. }))
+            .count();
+
+        let mut values = line.split_whitespace();
+        (1..=num_columns)
+            .map(|column| {
+                if column == num_columns {
+                    values.by_ref().collect::<Vec<&str>>().join(" ")
+                } else {
+                    values.next().unwrap_or_default().to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// The width of a number from its decimal separator, or from the end of its digits if it has none
+    /// (e.g. `)` or `%`).
+    fn decimals_width(&self, value: &str) -> usize {
+        let start = value
+            .rfind(self.decimal_separator)
+            .or_else(|| {
+                value
+                    .rfind(|ch: char| ch.is_ascii_digit())
+                    .map(|index| index + 1)
+            })
+            .unwrap_or(value.len());
+        self.metric().width(&value[start..])
+    }
+
+    /// How the values are measured.
+    fn metric(&self) -> Metric {
+        Metric::new(self.emoji_width, self.width_unit)
+    }
+
+    /// `numeric`: The widest decimals of each column which only has numbers.
+    fn format_values(&self, values: &[String], numeric: &[Option<usize>], bias: Bias) -> String {
+        let mut column = 0;
+        let mut result = String::new();
+
+        for field in &self.fields {
+            match field {
+                Field::Literal(text) => result.push_str(text),
+                Field::Column { align, width } => {
+                    let value = &values[column];
+                    let padded = match (align, numeric[column]) {
+                        (Some(align), _) => pad(value, *width, align, bias, self.metric()),
+                        (None, Some(decimals)) if self.auto_numeric && !value.is_empty() => {
+                            // pad the decimals so that the separators line up
+                            let value = format!(
+                                "{value}{}",
+                                " ".repeat(decimals - self.decimals_width(value))
+                            );
+                            pad(&value, *width, &Where::Right, bias, self.metric())
+                        }
+                        (None, _) => pad(value, *width, &Where::Left, bias, self.metric()),
+                    };
+                    result.push_str(&padded);
+                    column += 1;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Currency symbols which can precede a number.
+const CURRENCIES: [char; 6] = ['$', '€', '£', '¥', '₹', '¢'];
+
+/// Whether the value is a number, like `-12`, `3.5`, `1,000`, `1.234,50`, `20%`, `$9.99`,
+/// or `(45.00)` (a negative amount in accounting).
+pub(crate) fn is_number(value: &str) -> bool {
+    let unsigned = match value.strip_prefix('(') {
+        Some(value) => match value.strip_suffix(')') {
+            Some(value) => value,
+            None => return false,
+        },
+        None => value.strip_prefix(['-', '+']).unwrap_or(value),
+    };
+    let amount = unsigned.strip_prefix(CURRENCIES).unwrap_or(unsigned);
+    let amount = amount.strip_prefix(['-', '+']).unwrap_or(amount);
+    let digits = amount.strip_suffix('%').unwrap_or(amount);
+
+    digits.starts_with(|ch: char| ch.is_ascii_digit() || ch == '.' || ch == ',')
+        && digits.chars().any(|ch| ch.is_ascii_digit())
+        && digits
+            .chars()
+            .all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | ',' | '_' | '\''))
+}
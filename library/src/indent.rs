@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+
+use crate::width;
+
+/// Removes the leading white-spaces the non-blank lines have in common, keeping their relative indentation
+/// (unlike trimming them), and empties the blank lines.
+///
+/// # Example
+/// ```
+/// use align_text::{dedent, indent};
+///
+/// let code = vec![
+///     "    if ok {".to_string(),
+///     "        run();".to_string(),
+///     "  ".to_string(),
+///     "    }".to_string(),
+/// ];
+/// let dedented = dedent(&code);
+/// assert_eq!(dedented, vec!["if ok {", "    run();", "", "}"]);
+///
+/// // indented again, by a different amount
+/// assert_eq!(indent(&dedented, "  "), vec!["  if ok {", "      run();", "", "  }"]);
+/// ```
+pub fn dedent(lines: &[String]) -> Vec<String> {
+    let common = common_indentation(lines.iter().map(String::as_str));
+
+    lines
+        .iter()
+        .map(|line| match line.trim().is_empty() {
+            true => String::new(),
+            false => line[common..].to_string(),
+        })
+        .collect()
+}
+
+/// Starts the non-blank lines with `prefix`, e.g. spaces to indent them, leaving the blank lines as they are.
+pub fn indent(lines: &[String], prefix: &str) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| match line.trim().is_empty() {
+            true => line.clone(),
+            false => format!("{prefix}{line}"),
+        })
+        .collect()
+}
+
+/// The length in bytes of the leading white-spaces the non-blank lines have in common.
+pub(crate) fn common_indentation<'a>(lines: impl Iterator<Item = &'a str>) -> usize {
+    lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .reduce(|common, line| {
+            let len = common
+                .char_indices()
+                .zip(line.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(line.len()), |((index, _), _)| index);
+            &common[..len]
+        })
+        .unwrap_or_default()
+        .len()
+}
+
+/// Replaces the tabs with spaces up to the next multiple of `tab_width` columns (the escape sequences taking none).
+/// The line is only copied if it has tabs.
+pub(crate) fn expand_tabs(line: &str, tab_width: usize) -> Cow<'_, str> {
+    if !line.contains('\t') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for (index, part) in line.split('\t').enumerate() {
+        if index > 0 && tab_width > 0 {
+            let spaces = tab_width - column % tab_width;
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        }
+        expanded.push_str(part);
+        column += width::display_width(part);
+    }
+    Cow::Owned(expanded)
+}
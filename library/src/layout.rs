@@ -0,0 +1,1108 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::indent::{common_indentation, expand_tabs};
+use crate::width::{Metric, WidthCache};
+use crate::{width, Bias, Error, LineAligner, Options, Overflow, VWhere, Where, WordSplitter};
+
+/// How a line of the output is placed, see [`layout()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineLayout {
+    /// The line's content, after trimming and wrapping.
+    pub text: String,
+    /// The index of the input line it comes from (several output lines can come from the same input line when wrapping).
+    pub origin: usize,
+    /// The byte range of the input line it shows, e.g. to follow its characters (see [`Layout::offsets()`]).
+    /// The content can differ from it: its white-spaces changed, and a prefix, marker, or hyphen added when wrapping.
+    /// Empty for the lines without any of the input, e.g. the blank lines added around the text.
+    pub range: Range<usize>,
+    /// Number of spaces inserted before the content.
+    pub before: usize,
+    /// Number of spaces inserted after the content, to fill the columns.
+    pub after: usize,
+}
+
+impl LineLayout {
+    /// The text with spaces around it to fill the columns, placed according to the options.
+    pub(crate) fn padded(
+        text: String,
+        origin: usize,
+        range: Range<usize>,
+        columns: usize,
+        options: &Options,
+    ) -> LineLayout {
+        let space = columns.saturating_sub(options.width_of(&text));
+        let mut line = LineLayout {
+            text,
+            origin,
+            range,
+            before: 0,
+            after: space,
+        };
+        line.realign(&options.align, options.bias);
+        line
+    }
+
+    /// Moves the content according to `align`, keeping the same total of spaces around it.
+    pub fn realign(&mut self, align: &Where, bias: Bias) {
+        self.realign_with(align, bias);
+    }
+
+    /// The content with the spaces around it.
+    /// `keep_spaces`: Whether to keep the spaces on the right.
+    pub fn render(&self, keep_spaces: bool) -> String {
+        let after = if keep_spaces { self.after } else { 0 };
+        if self.before == 0 && after == 0 {
+            return self.text.clone();
+        }
+
+        let mut rendered = String::with_capacity(self.before + self.text.len() + after);
+        pad_with_spaces(&mut rendered, self.before);
+        rendered.push_str(&self.text);
+        pad_with_spaces(&mut rendered, after);
+        rendered
+    }
+
+    /// Like [`LineLayout::render()`], but reuses the content's buffer: a line without spaces before it isn't copied.
+    pub fn into_rendered(mut self, keep_spaces: bool) -> String {
+        if self.before > 0 {
+            return self.render(keep_spaces);
+        }
+
+        if keep_spaces {
+            pad_with_spaces(&mut self.text, self.after);
+        }
+        self.text
+    }
+}
+
+fn pad_with_spaces(text: &mut String, count: usize) {
+    text.extend(std::iter::repeat_n(' ', count));
+}
+
+/// The alignment of a block of text, computed without building the output lines.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    /// The final number of columns.
+    pub columns: usize,
+    pub lines: Vec<LineLayout>,
+}
+
+impl Layout {
+    /// Adds blank lines around the lines to fill a number of `rows`, placing them according to `valign`
+    /// (and `bias` when in the middle, left meaning towards the top).
+    /// With `paginate`, too many lines are split into pages of `rows` lines, each placed separately,
+    /// otherwise they fail with [`Error::InsufficientRows`].
+    ///
+    /// The blank lines are filled with spaces up to the columns, and take the origin of the line they're next to.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{Bias, Layout, VWhere};
+    ///
+    /// let mut layout = Layout::from(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+    /// layout.place_vertically(2, VWhere::Bottom, true, Bias::Left).unwrap();
+    ///
+    /// assert_eq!(layout.render(false), vec!["a", "b", "", "c"]);
+    /// ```
+    pub fn place_vertically(
+        &mut self,
+        rows: usize,
+        valign: VWhere,
+        paginate: bool,
+        bias: Bias,
+    ) -> Result<(), Error> {
+        if self.lines.len() > rows && (!paginate || rows == 0) {
+            return Err(Error::InsufficientRows);
+        }
+
+        let blank = |origin: usize| LineLayout {
+            text: String::new(),
+            origin,
+            range: 0..0,
+            before: 0,
+            after: self.columns,
+        };
+
+        let mut lines = Vec::with_capacity(self.lines.len().max(rows));
+        for page in self.lines.chunks(rows.max(1)) {
+            let space = rows - page.len();
+            let above = match valign {
+                VWhere::Top => 0,
+                VWhere::Middle => (space + usize::from(bias)) / 2,
+                VWhere::Bottom => space,
+            };
+            let below = space - above;
+
+            let first = page.first().map(|line| line.origin).unwrap_or(0);
+            let last = page.last().map(|line| line.origin).unwrap_or(0);
+
+            lines.extend((0..above).map(|_| blank(first)));
+            lines.extend(page.iter().cloned());
+            lines.extend((0..below).map(|_| blank(last)));
+        }
+
+        // no lines still fill the rows
+        if self.lines.is_empty() {
+            lines.extend((0..rows).map(|_| blank(0)));
+        }
+
+        self.lines = lines;
+        Ok(())
+    }
+
+    /// Aligns the lines which continue the previous one (the remainders of wrapped lines) according to `align`,
+    /// within the same columns.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Options, Overflow, Where};
+    ///
+    /// let lines = vec!["Hello World!".to_string()];
+    /// let options = Options {
+    ///     align: Where::Right,
+    ///     columns: Some(8),
+    ///     overflow: Overflow::Wrap,
+    ///     remainder: Some(Where::Left),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(layout(&lines, &options).unwrap().render(false), vec!["Hello Wo", "rld!"]);
+    /// ```
+    pub fn realign_continuations(&mut self, align: &Where, bias: Bias) {
+        let mut odd_lines = 0;
+        for index in 1..self.lines.len() {
+            if self.lines[index].origin != self.lines[index - 1].origin {
+                continue;
+            }
+
+            let line = &mut self.lines[index];
+            let bias = bias.for_line(line.before + line.after, &mut odd_lines);
+            line.realign(align, bias);
+        }
+    }
+
+    /// Aligns the last line of each paragraph according to `align`, within the same columns.
+    /// Paragraphs are separated by blank lines.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Options, Where};
+    ///
+    /// let lines = ["centered", "lines", "", "centered", "last"].map(String::from);
+    /// let options = Options {
+    ///     align: Where::Center,
+    ///     last_line: Some(Where::Left),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     layout(&lines, &options).unwrap().render(false),
+    ///     vec!["centered", "lines", "    ", "centered", "last"]
+    /// );
+    /// ```
+    pub fn realign_last_lines(&mut self, align: &Where, bias: Bias) {
+        let is_blank = |line: &LineLayout| line.text.trim().is_empty();
+        let mut odd_lines = 0;
+
+        for index in 0..self.lines.len() {
+            let last = match self.lines.get(index + 1) {
+                Some(next) => is_blank(next),
+                None => true,
+            };
+
+            if last && !is_blank(&self.lines[index]) {
+                let line = &mut self.lines[index];
+                let bias = bias.for_line(line.before + line.after, &mut odd_lines);
+                line.realign(align, bias);
+            }
+        }
+    }
+
+    /// Aligns each line according to the alignment of the input line it comes from (`aligns[origin]`), within the same columns.
+    /// Lines without an alignment in `aligns` are left as they are.
+    pub fn realign_each(&mut self, aligns: &[Where], bias: Bias) {
+        let mut odd_lines = 0;
+        for line in self.lines.iter_mut() {
+            if let Some(align) = aligns.get(line.origin) {
+                let bias = bias.for_line(line.before + line.after, &mut odd_lines);
+                line.realign(align, bias);
+            }
+        }
+    }
+
+    /// Removes the spaces at the end of the lines, in the content as well as those inserted after it.
+    pub fn trim_end(&mut self) {
+        for line in self.lines.iter_mut() {
+            line.text.truncate(line.text.trim_end().len());
+            line.after = 0;
+
+            if line.text.is_empty() {
+                line.before = 0;
+            }
+        }
+    }
+
+    /// Builds the output lines, see [`LineLayout::render()`].
+    pub fn render(&self, keep_spaces: bool) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| line.render(keep_spaces))
+            .collect()
+    }
+
+    /// Builds the output lines like [`Layout::render()`], but without copying the lines which have no spaces before them,
+    /// which is cheaper for mostly aligned text.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Options, Where};
+    ///
+    /// let lines = vec!["already".to_string(), "short".to_string()];
+    /// let options = Options {
+    ///     align: Where::Left,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let layout = layout(&lines, &options).unwrap();
+    /// assert_eq!(layout.clone().into_rendered(true), layout.render(true));
+    /// assert_eq!(layout.into_rendered(true), vec!["already", "short  "]);
+    /// ```
+    pub fn into_rendered(self, keep_spaces: bool) -> Vec<String> {
+        self.lines
+            .into_iter()
+            .map(|line| line.into_rendered(keep_spaces))
+            .collect()
+    }
+}
+
+impl From<Vec<String>> for Layout {
+    /// Lines as they are, without any inserted spaces.
+    fn from(lines: Vec<String>) -> Self {
+        Layout {
+            columns: lines
+                .iter()
+                .map(|line| width::display_width(line))
+                .max()
+                .unwrap_or(0),
+            lines: lines
+                .into_iter()
+                .enumerate()
+                .map(|(origin, text)| LineLayout {
+                    range: 0..text.len(),
+                    text,
+                    origin,
+                    before: 0,
+                    after: 0,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Computes how each line would be aligned with the given options, without building the output lines.
+/// See [`Error`] for potential errors returned.
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Bias, Options, Where};
+///
+/// let lines = vec!["Hello".to_string(), "big World!".to_string()];
+/// let options = Options::new(Where::Center, Some((8, true)), true, Bias::Left, false);
+/// let layout = layout(&lines, &options).unwrap();
+///
+/// assert_eq!(layout.columns, 8);
+/// assert_eq!(layout.lines.len(), 3);
+/// assert_eq!(layout.lines[2].text, "d!");
+/// assert_eq!(layout.lines[2].origin, 1);
+/// assert_eq!((layout.lines[2].before, layout.lines[2].after), (3, 3));
+/// assert_eq!(layout.render(false), vec![" Hello", "big Worl", "   d!"]);
+/// ```
+///
+/// * Shrinking lines which are slightly too wide:
+/// ```
+/// use align_text::{layout, Options, Where};
+///
+/// let lines = vec!["key   =   value".to_string()];
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(12),
+///     shrink: true,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(layout(&lines, &options).unwrap().render(false), vec![" key = value"]);
+/// ```
+///
+/// * Wrapping colored text, whose colors are carried over to the remainders:
+/// ```
+/// use align_text::{layout, Bias, Options, Where};
+///
+/// let lines = vec!["\x1b[1;31mfailed\x1b[0m: 3 tests".to_string()];
+/// let options = Options::new(Where::Left, Some((4, true)), false, Bias::Left, false);
+///
+/// assert_eq!(
+///     layout(&lines, &options).unwrap().render(false),
+///     vec!["\x1b[1;31mfail\x1b[0m", "\x1b[1;31med\x1b[0m: ", "3 te", "sts"]
+/// );
+///
+/// // terminal hyperlinks are measured as their text, and each piece links to the same place
+/// let lines = vec!["\x1b]8;;https://example.com\x1b\\example\x1b]8;;\x1b\\".to_string()];
+/// assert_eq!(
+///     layout(&lines, &options).unwrap().render(false),
+///     vec![
+///         "\x1b]8;;https://example.com\x1b\\exam\x1b]8;;\x1b\\",
+///         "\x1b]8;;https://example.com\x1b\\ple\x1b]8;;\x1b\\",
+///     ]
+/// );
+/// ```
+pub fn layout(lines: &[String], options: &Options) -> Result<Layout, Error> {
+    phase!("layout", lines = lines.len(), columns = ?options.columns);
+    if lines.is_empty() {
+        let mut layout = Layout::default();
+        if let Some(rows) = options.rows {
+            layout.place_vertically(rows, options.valign, options.paginate, options.bias)?;
+        }
+        return Ok(layout);
+    }
+
+    // the lines are borrowed while they're prepared and measured, those which change being copied then,
+    // and the others once they're put in the layout; the tabs are expanded first to measure the indentation
+    let expanded = lines
+        .iter()
+        .map(|line| match options.tab_width {
+            Some(tab_width) => expand_tabs(line, tab_width),
+            None => Cow::Borrowed(line.as_str()),
+        })
+        .collect::<Vec<Cow<str>>>();
+    let indentation = match options.dedent {
+        true => common_indentation(expanded.iter().map(|line| line.as_ref())),
+        false => 0,
+    };
+    let (mut lines, sources) = expanded
+        .iter()
+        .enumerate()
+        .map(|(origin, line)| {
+            let (start, text) = match line.trim().is_empty() {
+                true if options.dedent => (0, ""),
+                _ => (indentation, &line[indentation..]),
+            };
+            let (start, text) = match options.trim {
+                true => (start + text.len() - text.trim_start().len(), text.trim()),
+                false => (start, text),
+            };
+            let squeezed = options.squeeze
+                || (options.shrink
+                    && options
+                        .columns
+                        .is_some_and(|num| options.width_of(text) > num));
+            let text = if squeezed {
+                squeeze(text)
+            } else {
+                Cow::Borrowed(text)
+            };
+            let source = match (line, &text) {
+                (Cow::Borrowed(_), Cow::Borrowed(_)) => Source::Slice(start),
+                _ => Source::changed(&text, &lines[origin]),
+            };
+            ((origin, source.range(0..text.len()), text), source)
+        })
+        .unzip::<_, _, Vec<(usize, Range<usize>, Cow<str>)>, Vec<Source>>();
+
+    let metric = Metric::of(options);
+    let mut widths = measure_all(&lines, metric);
+    let text_width = widths
+        .iter()
+        .copied()
+        .max()
+        .ok_or(Error::UnknownError("couldn't caluclate text_width"))?;
+    event!(text_width, "measured the lines");
+
+    let num_cols = match options.columns {
+        None => text_width,
+        Some(num) if num < text_width => {
+            if options.overflow == Overflow::Error {
+                let line_index = widths
+                    .iter()
+                    .position(|&width| width == text_width)
+                    .map_or(0, |index| lines[index].0);
+                return Err(Error::InsufficientColumns {
+                    required: text_width,
+                    available: num,
+                    line_index,
+                });
+            }
+
+            // if wrap, split strings into substrings of width num
+            phase!("wrap", overflow = ?options.overflow);
+            let unbreakable = compile(&options.unbreakable)?;
+            lines = lines
+                .iter()
+                .flat_map(|(origin, _, line)| {
+                    let pieces = match wrap(line, num, *origin, &unbreakable, options) {
+                        Ok(pieces) => pieces,
+                        Err(error) => return vec![Err(error)],
+                    };
+                    pieces
+                        .into_iter()
+                        .map(|(piece, range)| {
+                            Ok((*origin, sources[*origin].range(range), Cow::Owned(piece)))
+                        })
+                        .collect()
+                })
+                .collect::<Result<_, Error>>()?;
+            widths = measure_all(&lines, metric);
+            event!(lines = lines.len(), "wrapped the lines");
+
+            num
+        }
+        Some(num) => num,
+    };
+
+    // align by adding spaces before and after
+    phase!("pad", columns = num_cols);
+    let lines = lines
+        .into_iter()
+        .zip(widths)
+        .map(|((origin, range, text), width)| {
+            let mut line = LineLayout {
+                text: text.into_owned(),
+                origin,
+                range,
+                before: 0,
+                after: num_cols.saturating_sub(width),
+            };
+            line.realign(&options.align, options.bias);
+            line
+        })
+        .collect();
+
+    let mut layout = Layout {
+        columns: num_cols,
+        lines,
+    };
+    if options.bias == Bias::Alternate {
+        // the lines were padded on their own
+        layout.realign_with(&options.align, options.bias);
+    }
+
+    if let Some(remainder) = &options.remainder {
+        layout.realign_continuations(remainder, options.bias);
+    }
+    if let Some(last_line) = &options.last_line {
+        layout.realign_last_lines(last_line, options.bias);
+    }
+
+    if let Some(rows) = options.rows {
+        layout.place_vertically(rows, options.valign, options.paginate, options.bias)?;
+    }
+
+    Ok(layout)
+}
+
+/// Normalizes the lines and aligns them in one pass, returning the aligned lines:
+/// expands the tabs ([`Options::tab_width`]), removes the common indentation ([`Options::dedent`]),
+/// trims the lines ([`Options::trim`]), and squeezes their spaces ([`Options::squeeze`]),
+/// without copying the lines between the steps, nor traversing them for each.
+///
+/// # Example
+/// ```
+/// use align_text::{normalize_and_align, Options};
+///
+/// let lines = vec![
+///     "    fn main() {".to_string(),
+///     "    \tprintln!(\"{}\",   1);".to_string(),
+///     "    }".to_string(),
+/// ];
+/// let options = Options {
+///     tab_width: Some(4),
+///     dedent: true,
+///     squeeze: true,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     normalize_and_align(&lines, &options).unwrap(),
+///     vec!["fn main() {", "    println!(\"{}\", 1);", "}"]
+/// );
+/// ```
+pub fn normalize_and_align(lines: &[String], options: &Options) -> Result<Vec<String>, Error> {
+    Ok(layout(lines, options)?.into_rendered(options.keep_spaces))
+}
+
+/// Like [`layout()`], but each line is aligned according to its own alignment in `aligns`.
+/// Lines without one (if `aligns` is shorter) are aligned according to `options.align`.
+///
+/// # Example
+/// ```
+/// use align_text::{align_each, Options, Where};
+///
+/// let lines = ["Title", "body text", "footer"].map(String::from);
+/// let aligns = [Where::Center, Where::Left, Where::Right];
+/// let options = Options {
+///     columns: Some(11),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     align_each(&lines, &aligns, &options).unwrap().render(false),
+///     vec!["   Title", "body text", "     footer"]
+/// );
+/// ```
+pub fn align_each(lines: &[String], aligns: &[Where], options: &Options) -> Result<Layout, Error> {
+    // the blank lines of the vertical placement aren't realigned
+    let horizontal = Options {
+        rows: None,
+        ..options.clone()
+    };
+    let mut layout = layout(lines, &horizontal)?;
+    layout.realign_each(aligns, options.bias);
+
+    if let Some(rows) = options.rows {
+        layout.place_vertically(rows, options.valign, options.paginate, options.bias)?;
+    }
+
+    Ok(layout)
+}
+
+/// Aligns the lines as a block, like `align --inner ... --outer ...`: the lines are aligned within the block
+/// (as wide as the widest line) according to `inner`, then the block is placed within the columns according to `options.align`.
+/// [`Options::remainder`] and [`Options::last_line`] apply within the block,
+/// and the spaces on the right are removed unless [`Options::keep_spaces`] is set.
+/// When both are [`Where::Center`], the lines are centered within all the columns at once, like [`layout()`],
+/// so that they don't lean twice to the same side.
+///
+/// # Example
+/// ```
+/// use align_text::{align_block, layout, Options, Where};
+///
+/// let lines = ["a", "bbb", "cc"].map(String::from);
+/// let options = Options {
+///     align: Where::Center,
+///     columns: Some(7),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     align_block(&lines, &Where::Right, &options).unwrap().render(false),
+///     vec!["    a", "  bbb", "   cc"]
+/// );
+/// assert_eq!(
+///     align_block(&lines, &Where::Left, &options).unwrap().render(false),
+///     vec!["  a", "  bbb", "  cc"]
+/// );
+/// assert_eq!(
+///     align_block(&lines, &Where::Center, &options).unwrap(),
+///     layout(&lines, &options).unwrap()
+/// );
+/// ```
+pub fn align_block(lines: &[String], inner: &Where, options: &Options) -> Result<Layout, Error> {
+    if *inner == Where::Center && options.align == Where::Center {
+        // center completely
+        return layout(lines, options);
+    }
+
+    align_block_with(lines, inner, options)
+}
+
+/// Like [`align_block()`], but the lines are placed within the block by any [`LineAligner`].
+///
+/// # Example
+/// ```
+/// use align_text::{align_block_with, OpticalCenter, Options, Where};
+///
+/// let lines = ["Hello!", "Hi"].map(String::from);
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(8),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     align_block_with(&lines, &OpticalCenter, &options).unwrap().render(false),
+///     vec!["  Hello!", "    Hi"]
+/// );
+/// ```
+pub fn align_block_with(
+    lines: &[String],
+    inner: &dyn LineAligner,
+    options: &Options,
+) -> Result<Layout, Error> {
+    // wrap first, so the spaces from the inner align don't get wrapped with the text
+    let wrapped = layout(
+        lines,
+        &Options {
+            align: Where::Left,
+            remainder: None,
+            last_line: None,
+            rows: None,
+            ..options.clone()
+        },
+    )?;
+    let texts = wrapped
+        .lines
+        .iter()
+        .map(|line| line.text.clone())
+        .collect::<Vec<String>>();
+
+    // inner align
+    let inner_options = Options::new(Where::Left, None, false, options.bias, true);
+    let mut block = layout(&texts, &inner_options)?;
+    // the inner layout doesn't know which lines were wrapped
+    for (line, wrapped) in block.lines.iter_mut().zip(&wrapped.lines) {
+        line.origin = wrapped.origin;
+    }
+    block.realign_with(inner, options.bias);
+    if let Some(remainder) = &options.remainder {
+        block.realign_continuations(remainder, options.bias);
+    }
+    if let Some(last_line) = &options.last_line {
+        block.realign_last_lines(last_line, options.bias);
+    }
+
+    // outer align
+    let outer_options = Options {
+        remainder: None,
+        last_line: None,
+        trim: false,
+        shrink: false,
+        squeeze: false,
+        tab_width: None,
+        dedent: false,
+        rows: None,
+        ..options.clone()
+    };
+    let outer = layout(&block.render(true), &outer_options)?;
+
+    let mut result = Layout {
+        columns: outer.columns,
+        lines: wrapped
+            .lines
+            .into_iter()
+            .zip(block.lines.iter().zip(outer.lines.iter()))
+            .map(|(line, (inner, outer))| LineLayout {
+                before: inner.before + outer.before,
+                after: inner.after + outer.after,
+                ..line
+            })
+            .collect(),
+    };
+
+    if !options.keep_spaces {
+        // remove spaces introduced in inner align
+        result.trim_end();
+    }
+    if let Some(rows) = options.rows {
+        result.place_vertically(rows, options.valign, options.paginate, options.bias)?;
+    }
+
+    Ok(result)
+}
+
+/// Aligns each block of lines separately (see [`layout()`]), but within the same number of columns:
+/// `options.columns`, or the widest block's width if it's `None`, so that the sections of a report line up.
+///
+/// The `line_index` of an [`Error::InsufficientColumns`] is the index in all the lines, one block after another.
+///
+/// # Example
+/// ```
+/// use align_text::{align_group, Options, Where};
+///
+/// let blocks = vec![
+///     vec!["Summary".to_string(), "all good".to_string()],
+///     vec!["Details".to_string(), "nothing to report".to_string()],
+/// ];
+/// let options = Options {
+///     align: Where::Center,
+///     ..Default::default()
+/// };
+///
+/// let layouts = align_group(&blocks, &options).unwrap();
+/// assert_eq!(layouts[0].render(true), vec!["     Summary     ", "    all good     "]);
+/// assert_eq!(layouts[1].columns, 17);
+/// ```
+pub fn align_group(blocks: &[Vec<String>], options: &Options) -> Result<Vec<Layout>, Error> {
+    let mut offset = 0;
+    let mut layouts = vec![];
+    for lines in blocks {
+        let start = offset;
+        layouts.push(layout(lines, options).map_err(|e| e.map_line_index(|index| start + index))?);
+        offset += lines.len();
+    }
+
+    if options.columns.is_none() {
+        let columns = layouts
+            .iter()
+            .map(|layout| layout.columns)
+            .max()
+            .unwrap_or_default();
+        let shared = Options {
+            columns: Some(columns),
+            ..options.clone()
+        };
+        for (lines, block_layout) in blocks.iter().zip(layouts.iter_mut()) {
+            // the widest blocks are already aligned within the columns
+            if block_layout.columns < columns {
+                *block_layout = layout(lines, &shared)?;
+            }
+        }
+    }
+
+    Ok(layouts)
+}
+
+/// Like [`layout()`], but each output line is aligned within its own number of columns, given by `widths`
+/// from its index (after wrapping), e.g. to flow text around a figure. `options.columns` is ignored,
+/// and the final number of columns is the widest line's.
+///
+/// # Example
+/// ```
+/// use align_text::{layout_with_widths, Options, Overflow, Where};
+///
+/// let lines = vec!["one two three four".to_string()];
+/// let widths = [4, 8, 12];
+/// let options = Options {
+///     align: Where::Right,
+///     overflow: Overflow::Wrap,
+///     ..Default::default()
+/// };
+///
+/// let layout = layout_with_widths(&lines, |index| widths[index.min(2)], &options).unwrap();
+/// assert_eq!(layout.columns, 12);
+/// assert_eq!(layout.render(false), vec!["one ", "two thre", "      e four"]);
+/// ```
+pub fn layout_with_widths(
+    lines: &[String],
+    widths: impl Fn(usize) -> usize,
+    options: &Options,
+) -> Result<Layout, Error> {
+    // only trim and squeeze
+    let prepared = layout(
+        lines,
+        &Options {
+            align: Where::Left,
+            columns: None,
+            remainder: None,
+            last_line: None,
+            shrink: false,
+            rows: None,
+            ..options.clone()
+        },
+    )?;
+
+    let unbreakable = compile(&options.unbreakable)?;
+    let mut result = Vec::with_capacity(prepared.lines.len());
+    for line in prepared.lines {
+        let input = &lines[line.origin];
+        let source = match input.get(line.range.clone()) == Some(line.text.as_str()) {
+            true => Source::Slice(line.range.start),
+            false => Source::changed(&line.text, input),
+        };
+        let range = |range: Range<usize>| {
+            let range = source.range(range);
+            range.start.min(line.range.end)..range.end.min(line.range.end)
+        };
+        let mut text = line.text;
+        let mut continuation = false;
+        // where the text is in the prepared line, after what was added at its start
+        let (mut start, mut added) = (0, 0);
+        loop {
+            let columns = widths(result.len());
+            if options.shrink && options.width_of(&text) > columns {
+                text = squeeze(&text).into_owned();
+            }
+
+            let text_width = options.width_of(&text);
+            if text_width > columns {
+                if options.overflow == Overflow::Error {
+                    return Err(Error::InsufficientColumns {
+                        required: text_width,
+                        available: columns,
+                        line_index: line.origin,
+                    });
+                }
+
+                // the first piece fits, the rest goes on the next lines
+                let marker = &options.wrap_marker;
+                let available = wrapping_room(columns, line.origin, options)?;
+                let (mut piece, piece_range, rest, added_to_rest) =
+                    split_first(&text, available, &unbreakable, continuation, options);
+                if !rest.is_empty() {
+                    piece.push_str(marker);
+                }
+                let at = |index: usize| start + index.saturating_sub(added);
+                let piece_range = range(at(piece_range.start)..at(piece_range.end));
+                result.push(LineLayout::padded(
+                    piece,
+                    line.origin,
+                    piece_range,
+                    columns,
+                    options,
+                ));
+                // word wrapping drops the spaces at the break, which may be all that's left
+                if rest.is_empty() {
+                    break;
+                }
+                start = at(text.len() - (rest.len() - added_to_rest));
+                added = added_to_rest;
+                text = rest;
+                continuation = true;
+                continue;
+            }
+
+            let text_range = range(start..start + text.len() - added);
+            result.push(LineLayout::padded(
+                text,
+                line.origin,
+                text_range,
+                columns,
+                options,
+            ));
+            break;
+        }
+    }
+
+    let mut layout = Layout {
+        columns: result
+            .iter()
+            .map(|line| line.before + options.width_of(&line.text) + line.after)
+            .max()
+            .unwrap_or(0),
+        lines: result,
+    };
+    if options.bias == Bias::Alternate {
+        layout.realign_with(&options.align, options.bias);
+    }
+
+    if let Some(remainder) = &options.remainder {
+        layout.realign_continuations(remainder, options.bias);
+    }
+    if let Some(last_line) = &options.last_line {
+        layout.realign_last_lines(last_line, options.bias);
+    }
+
+    if let Some(rows) = options.rows {
+        layout.place_vertically(rows, options.valign, options.paginate, options.bias)?;
+    }
+
+    Ok(layout)
+}
+
+/// Where the bytes of a prepared line (see [`layout()`]) come from in the input line.
+enum Source {
+    /// The prepared line is a slice of the input line starting at this byte.
+    Slice(usize),
+    /// Its white-spaces were changed (e.g. tabs expanded, or squeezed): for each byte,
+    /// the byte range of the input character it comes from, and then the input line's length.
+    Changed(Vec<Range<usize>>, usize),
+}
+
+impl Source {
+    /// Follows the white-spaces of the `prepared` line back to those of the input `line`, the only changes made to it.
+    /// The spaces of an expanded tab come from the tab, and a run shortened (e.g. squeezed or dedented) from its end.
+    fn changed(prepared: &str, line: &str) -> Source {
+        let mut bytes = Vec::with_capacity(prepared.len());
+        let mut input = line.char_indices().peekable();
+        let mut chars = prepared.chars().peekable();
+        let range = |(index, ch): (usize, char)| index..index + ch.len_utf8();
+
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() {
+                let run = std::iter::from_fn(|| chars.next_if(|ch| ch.is_whitespace()))
+                    .collect::<Vec<char>>();
+                let spaces = std::iter::from_fn(|| input.next_if(|(_, ch)| ch.is_whitespace()))
+                    .map(range)
+                    .collect::<Vec<Range<usize>>>();
+                for (index, ch) in run.iter().enumerate() {
+                    let source = (index + spaces.len())
+                        .checked_sub(run.len())
+                        .and_then(|index| spaces.get(index))
+                        .or(spaces.first())
+                        .map_or(line.len()..line.len(), Range::clone);
+                    bytes.extend(std::iter::repeat_n(source, ch.len_utf8()));
+                }
+                continue;
+            }
+
+            // the white-spaces which were removed (e.g. trimmed) are skipped
+            while input.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+            let source = input.next().map_or(line.len()..line.len(), range);
+            bytes.extend(std::iter::repeat_n(source, ch.len_utf8()));
+            chars.next();
+        }
+        Source::Changed(bytes, line.len())
+    }
+
+    /// The byte range of the input line which the `range` of the prepared line shows.
+    fn range(&self, range: Range<usize>) -> Range<usize> {
+        match self {
+            Source::Slice(start) => start + range.start..start + range.end,
+            Source::Changed(bytes, len) => {
+                let start = bytes.get(range.start).map_or(*len, |source| source.start);
+                match range.is_empty() {
+                    true => start..start,
+                    false => start..bytes.get(range.end - 1).map_or(*len, |source| source.end),
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the runs of white-spaces inside the line with a single space, keeping those around it.
+fn squeeze(line: &str) -> Cow<'_, str> {
+    let content = line.trim();
+    if !content.contains("  ") && !content.contains(|ch: char| ch.is_whitespace() && ch != ' ') {
+        return Cow::Borrowed(line);
+    }
+
+    let start = line.len() - line.trim_start().len();
+    let end = start + content.len();
+
+    let squeezed = content.split_whitespace().collect::<Vec<&str>>().join(" ");
+    Cow::Owned(format!("{}{squeezed}{}", &line[..start], &line[end..]))
+}
+
+/// Splits the line into pieces which fit in `columns`, the continuations starting with [`Options::subsequent_prefix`],
+/// and those continued ending with [`Options::wrap_marker`].
+/// Each piece comes with the byte range of the line it shows.
+/// Fails like [`wrapping_room()`] when the line has to be wrapped.
+fn wrap(
+    line: &str,
+    columns: usize,
+    line_index: usize,
+    unbreakable: &[Regex],
+    options: &Options,
+) -> Result<Vec<(String, Range<usize>)>, Error> {
+    if options.width_of(line) <= columns {
+        return Ok(wrap_unmarked(line, columns, unbreakable, options));
+    }
+
+    // the marker takes some of the columns of the pieces it ends
+    let marker = &options.wrap_marker;
+    let columns = wrapping_room(columns, line_index, options)?;
+    let mut pieces = wrap_unmarked(line, columns, unbreakable, options);
+    if !marker.is_empty() {
+        let last = pieces.len() - 1;
+        for (piece, _) in pieces[..last].iter_mut() {
+            piece.push_str(marker);
+        }
+    }
+    Ok(pieces)
+}
+
+/// The columns left for the text of the pieces of a wrapped line, before the [`Options::wrap_marker`] which ends them.
+/// Fails with [`Error::InsufficientColumns`] if the marker, with the [`Options::subsequent_prefix`] which starts
+/// the continuations (and the hyphen which may end them, see [`WordSplitter::Hyphenate`]), leaves no room for their text.
+fn wrapping_room(columns: usize, line_index: usize, options: &Options) -> Result<usize, Error> {
+    let marker = options.width_of(&options.wrap_marker);
+    let prefix = options.width_of(&options.subsequent_prefix);
+    // without a prefix, the words aren't hyphenated when there's room for only one column
+    let hyphen = usize::from(
+        prefix > 0
+            && options.overflow == Overflow::WordWrap
+            && options.word_splitter == WordSplitter::Hyphenate,
+    );
+    let available = columns.saturating_sub(marker);
+    if prefix + hyphen >= available {
+        return Err(Error::InsufficientColumns {
+            required: marker + prefix + 1 + hyphen,
+            available: columns,
+            line_index,
+        });
+    }
+    Ok(available)
+}
+
+/// Splits the line into pieces which fit in `columns`, see [`wrap()`].
+fn wrap_unmarked(
+    line: &str,
+    columns: usize,
+    unbreakable: &[Regex],
+    options: &Options,
+) -> Vec<(String, Range<usize>)> {
+    if options.subsequent_prefix.is_empty() {
+        let ranges = find_all(unbreakable, line);
+        return width::split(
+            line,
+            columns,
+            &ranges,
+            options.overflow,
+            options.word_splitter,
+            Metric::of(options),
+        );
+    }
+
+    let mut pieces = vec![];
+    let mut text = line.to_string();
+    // where the text is in the line, after what was added at its start
+    let (mut start, mut added) = (0, 0);
+    loop {
+        let (piece, range, rest, added_to_rest) =
+            split_first(&text, columns, unbreakable, !pieces.is_empty(), options);
+        let at = |index: usize| start + index.saturating_sub(added);
+        pieces.push((piece, at(range.start)..at(range.end)));
+        if rest.is_empty() {
+            return pieces;
+        }
+        start = at(text.len() - (rest.len() - added_to_rest));
+        added = added_to_rest;
+        text = rest;
+    }
+}
+
+/// Splits the first piece which fits in `columns` off the text, and returns it with the rest,
+/// which starts with [`Options::subsequent_prefix`].
+/// The piece comes with its byte range in the text, and the rest with the number of bytes added at its start.
+/// `continuation`: Whether the text continues a wrapped line, its prefix then going with the first character after it.
+fn split_first(
+    text: &str,
+    columns: usize,
+    unbreakable: &[Regex],
+    continuation: bool,
+    options: &Options,
+) -> (String, Range<usize>, String, usize) {
+    let prefix = &options.subsequent_prefix;
+    let mut ranges = find_all(unbreakable, text);
+    if continuation && !prefix.is_empty() {
+        ranges.push(0..prefix.len() + width::unit_len(&text[prefix.len()..]));
+    }
+
+    let (piece, range, rest, start) = width::split_first(
+        text,
+        columns,
+        &ranges,
+        options.overflow,
+        options.word_splitter,
+        Metric::of(options),
+    );
+    if rest.is_empty() {
+        (piece, range, rest, 0)
+    } else {
+        let added = prefix.len() + rest.len() - (text.len() - start);
+        (piece, range, format!("{prefix}{rest}"), added)
+    }
+}
+
+/// The widths of the lines, see [`WidthCache`].
+fn measure_all(lines: &[(usize, Range<usize>, Cow<str>)], metric: Metric) -> Vec<usize> {
+    let mut cache = WidthCache::new(metric);
+    lines.iter().map(|(.., line)| cache.width(line)).collect()
+}
+
+/// Compiles the unbreakable patterns, see [`Options::unbreakable`].
+pub(crate) fn compile(patterns: &[String]) -> Result<Vec<Regex>, Error> {
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| Error::InvalidPattern(e.to_string())))
+        .collect()
+}
+
+/// The byte ranges of the matches of all the regular expressions in the line.
+pub(crate) fn find_all(regexes: &[Regex], line: &str) -> Vec<Range<usize>> {
+    regexes
+        .iter()
+        .flat_map(|regex| regex.find_iter(line).map(|found| found.range()))
+        .collect()
+}
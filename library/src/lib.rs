@@ -1,35 +1,112 @@
+// the macros are used in the other modules
+#[macro_use]
+mod trace;
+
+mod aligner;
+mod ansi;
+mod backslash;
+mod block;
+mod diagnostic;
+mod display;
+mod emoji;
+mod ext;
+mod flow;
+mod format;
+mod indent;
+mod layout;
+mod metrics;
+mod normalize;
+mod offsets;
+mod operator;
+mod options;
+mod overlay;
+mod pipeline;
+mod plan;
+mod record;
+mod region;
+mod reveal;
+#[cfg(feature = "ropey")]
+mod rope;
+mod separator;
+mod table;
+mod timestamp;
+mod transpose;
+mod unalign;
+mod width;
+mod writer;
+
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[cfg(feature = "clap_arg")]
 use clap::ValueEnum;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "derive")]
+pub use align_text_derive::AlignRow;
+
+pub use aligner::{Aligners, At, LineAligner, OpticalCenter};
+pub use backslash::align_backslashes;
+pub use block::AlignedBlock;
+pub use diagnostic::{diagnose, Diagnostic};
+pub use display::Aligned;
+pub use emoji::EmojiWidth;
+pub use ext::AlignStr;
+pub use flow::{flow_columns, flow_layout};
+pub use format::{Field, LineFormat};
+pub use indent::{dedent, indent};
+pub use layout::{
+    align_block, align_block_with, align_each, align_group, layout, layout_with_widths,
+    normalize_and_align, Layout, LineLayout,
+};
+pub use metrics::{measure, measure_with, width_percentile, width_percentile_with, TextMetrics};
+pub use normalize::{normalize_unicode, NormalizationForm};
+pub use offsets::LineOffsets;
+pub use operator::{align_operators, ASSIGNMENT_OPERATORS};
+pub use options::{Options, Overflow, WordSplitter, MARKDOWN_PATTERNS, PATH_PATTERN, URL_PATTERN};
+pub use overlay::overlay;
+pub use pipeline::{Pipeline, Step};
+pub use plan::{plan, Plan, PlannedLine};
+pub use record::{RecordField, RecordLayout, Truncate};
+pub use region::align_region;
+pub use reveal::{escape_non_ascii, reveal, Placeholders};
+#[cfg(feature = "ropey")]
+pub use rope::align_rope;
+pub use separator::{normalize_separators, LineSeparators, LINE_SEPARATORS};
+pub use table::{align_cells, column_widths, table, AlignRow};
+pub use timestamp::{align_timestamps, TIMESTAMP_PATTERN};
+pub use transpose::{rotate, transpose, Rotation};
+pub use unalign::unalign;
+pub use width::{display_width, WidthUnit};
+pub use writer::{AlignFmtWriter, AlignWriter};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum Where {
+    #[default]
     Left,
     Center,
     Right,
 }
 
-impl Default for Where {
-    fn default() -> Self {
-        Where::Left
+impl FromStr for Where {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "l" | "left" => Ok(Where::Left),
+            "c" | "center" => Ok(Where::Center),
+            "r" | "right" => Ok(Where::Right),
+            _ => Err("invalid Where value".to_string()),
+        }
     }
 }
 
 #[cfg(feature = "clap_arg")]
 impl ValueEnum for Where {
     fn from_str(input: &str, ignore_case: bool) -> Result<Self, String> {
-        let input = if ignore_case {
-            input.to_lowercase()
+        if ignore_case {
+            input.to_lowercase().parse()
         } else {
-            input.to_string()
-        };
-
-        match input.as_str() {
-            "l" | "left" => Ok(Where::Left),
-            "c" | "center" => Ok(Where::Center),
-            "r" | "right" => Ok(Where::Right),
-            _ => Err("invalid Where value".to_string()),
+            input.parse()
         }
     }
 
@@ -46,42 +123,118 @@ impl ValueEnum for Where {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum Bias {
-    Left,
-    Right,
+/// Where to align the lines vertically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VWhere {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
 }
 
-impl Default for Bias {
-    fn default() -> Self {
-        Bias::Left
+impl FromStr for VWhere {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "t" | "top" => Ok(VWhere::Top),
+            "m" | "middle" => Ok(VWhere::Middle),
+            "b" | "bottom" => Ok(VWhere::Bottom),
+            _ => Err("invalid VWhere value".to_string()),
+        }
     }
 }
 
 #[cfg(feature = "clap_arg")]
-impl ValueEnum for Bias {
+impl ValueEnum for VWhere {
     fn from_str(input: &str, ignore_case: bool) -> Result<Self, String> {
-        let input = if ignore_case {
-            input.to_lowercase()
+        if ignore_case {
+            input.to_lowercase().parse()
         } else {
-            input.to_string()
-        };
+            input.parse()
+        }
+    }
+
+    fn value_variants<'a>() -> &'a [Self] {
+        &[VWhere::Top, VWhere::Middle, VWhere::Bottom]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        match self {
+            VWhere::Top => Some(clap::builder::PossibleValue::new("top").alias("t")),
+            VWhere::Middle => Some(clap::builder::PossibleValue::new("middle").alias("m")),
+            VWhere::Bottom => Some(clap::builder::PossibleValue::new("bottom").alias("b")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Bias {
+    #[default]
+    Left,
+    Right,
+    /// Left for the first line which can't be centered perfectly, right for the next one, and so on,
+    /// so that a centered block of short lines doesn't lean to one side. A line aligned on its own leans left.
+    Alternate,
+}
+
+impl Bias {
+    /// The side a line with `space` free columns leans towards, `odd_lines` counting the lines
+    /// with an odd number of free columns so far for [`Bias::Alternate`], to align lines one by one.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::Bias;
+    ///
+    /// let mut odd_lines = 0;
+    /// let biases = [3, 2, 5, 1].map(|space| Bias::Alternate.for_line(space, &mut odd_lines));
+    /// assert_eq!(biases, [Bias::Left, Bias::Alternate, Bias::Right, Bias::Left]);
+    /// ```
+    pub fn for_line(self, space: usize, odd_lines: &mut usize) -> Bias {
+        if self != Bias::Alternate || space.is_multiple_of(2) {
+            return self;
+        }
+
+        *odd_lines += 1;
+        match *odd_lines % 2 {
+            1 => Bias::Left,
+            _ => Bias::Right,
+        }
+    }
+}
 
-        match input.as_str() {
+impl FromStr for Bias {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
             "l" | "left" => Ok(Bias::Left),
             "r" | "right" => Ok(Bias::Right),
+            "a" | "alternate" => Ok(Bias::Alternate),
             _ => Err("invalid Bias value".to_string()),
         }
     }
+}
+
+#[cfg(feature = "clap_arg")]
+impl ValueEnum for Bias {
+    fn from_str(input: &str, ignore_case: bool) -> Result<Self, String> {
+        if ignore_case {
+            input.to_lowercase().parse()
+        } else {
+            input.parse()
+        }
+    }
 
     fn value_variants<'a>() -> &'a [Self] {
-        &[Bias::Left, Bias::Right]
+        &[Bias::Left, Bias::Right, Bias::Alternate]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         match self {
             Bias::Left => Some(clap::builder::PossibleValue::new("left").alias("l")),
             Bias::Right => Some(clap::builder::PossibleValue::new("right").alias("r")),
+            Bias::Alternate => Some(clap::builder::PossibleValue::new("alternate").alias("a")),
         }
     }
 }
@@ -89,36 +242,101 @@ impl ValueEnum for Bias {
 impl From<Bias> for usize {
     fn from(value: Bias) -> Self {
         match value {
-            Bias::Left => 0,
+            Bias::Left | Bias::Alternate => 0,
             Bias::Right => 1,
         }
     }
 }
 
 /// Errors returned by [`align_text()`]:
-/// * [`Error::InsufficientColumns`]: the `lines` can't fit in the given number of `columns`.
+/// * [`Error::InsufficientColumns`]: the `lines` can't fit in the given number of `columns`,
+///   with the index of the widest line, its width (after trimming), and the number of columns.
+/// * [`Error::InsufficientRows`]: there are more `lines` than the given number of `rows` (see [`Options`]).
+/// * [`Error::InvalidPattern`]: one of the [`Options::unbreakable`] patterns isn't a valid regular expression.
+/// * [`Error::StraySeparator`]: a line has a line separator inside it, see [`normalize_separators()`].
+/// * [`Error::UnsupportedOption`]: an option which [`plan()`] can't take into account is set, with its name.
 /// * [`Error::UnknownError`]: an unexpected error that shouldn't have occured.
 ///
 /// # Example
 /// * Passing an insufficient number of columns:
 /// ```
 /// use align_text::{Align, Where, Bias, Error};
-/// 
+///
 /// let mut lines = vec!["0123456789".to_string()];
 /// let result = lines.align_text(Where::Center, Some((3, false)), true, Bias::Right, true);
-/// 
-/// assert_eq!(result, Err(Error::InsufficientColumns));
+///
+/// assert_eq!(
+///     result,
+///     Err(Error::InsufficientColumns {
+///         required: 10,
+///         available: 3,
+///         line_index: 0
+///     })
+/// );
 /// ```
+///
+/// More variants may be added, so matches on it need a wildcard arm.
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
-    InsufficientColumns,
+    InsufficientColumns {
+        required: usize,
+        available: usize,
+        line_index: usize,
+    },
+    InsufficientRows,
+    InvalidPattern(String),
+    StraySeparator {
+        line_index: usize,
+    },
+    UnsupportedOption(&'static str),
     UnknownError(&'static str),
 }
 
+impl Error {
+    /// Changes the index of the line in [`Error::InsufficientColumns`] and [`Error::StraySeparator`],
+    /// e.g. to the index in the whole text when only a part of it was aligned.
+    pub fn map_line_index(self, f: impl FnOnce(usize) -> usize) -> Error {
+        match self {
+            Error::InsufficientColumns {
+                required,
+                available,
+                line_index,
+            } => Error::InsufficientColumns {
+                required,
+                available,
+                line_index: f(line_index),
+            },
+            Error::StraySeparator { line_index } => Error::StraySeparator {
+                line_index: f(line_index),
+            },
+            e => e,
+        }
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::InsufficientColumns => write!(f, "text can't fit, not enough columns"),
+            Error::InsufficientColumns {
+                required,
+                available,
+                line_index,
+            } => write!(
+                f,
+                "text can't fit, not enough columns: line {} is {required} wide, {available} available",
+                line_index + 1
+            ),
+            Error::InsufficientRows => write!(f, "text can't fit, not enough rows"),
+            Error::InvalidPattern(e) => write!(f, "invalid unbreakable pattern, {e}"),
+            Error::StraySeparator { line_index } => write!(
+                f,
+                "line {} has a stray line separator (\\r, U+2028, or U+2029) in it",
+                line_index + 1
+            ),
+            Error::UnsupportedOption(option) => {
+                write!(f, "the {option} option isn't supported here")
+            }
             Error::UnknownError(e) => write!(f, "unexpected, {e}"),
         }
     }
@@ -179,67 +397,8 @@ impl Align for Vec<String> {
         bias: Bias,
         keep_spaces: bool,
     ) -> Result<Vec<String>, Error> {
-        let mut lines = self.clone();
-
-        if lines.is_empty() {
-            return Ok(lines);
-        }
-
-        if trim {
-            lines
-                .iter_mut()
-                .for_each(|line| *line = line.trim().to_string());
-        }
-
-        let text_width = lines
-            .iter()
-            .map(|line| line.len())
-            .max()
-            .ok_or(Error::UnknownError("couldn't caluclate text_width"))?;
-
-        let num_cols = match columns {
-            None => text_width,
-            Some((num, wrap)) if num < text_width => {
-                if !wrap {
-                    return Err(Error::InsufficientColumns);
-                }
-
-                // if wrap, split strings into substrings of length num
-                lines = lines
-                    .iter()
-                    .flat_map(|line| {
-                        line.chars()
-                            .collect::<Vec<char>>()
-                            .chunks(num)
-                            .map(|line_chars| line_chars.iter().collect::<String>())
-                            .collect::<Vec<String>>()
-                    })
-                    .collect();
-
-                num
-            }
-            Some((num, _)) => num,
-        };
-
-        // align by adding spaces before and after
-        for line in lines.iter_mut() {
-            let space = num_cols - line.len();
-
-            let before = match align {
-                Where::Left => 0,
-                Where::Center => (space + usize::from(bias)) / 2,
-                Where::Right => space,
-            };
-            let after = space - before;
-
-            line.insert_str(0, " ".repeat(before).as_str());
-
-            if keep_spaces {
-                line.push_str(" ".repeat(after).as_str());
-            }
-        }
-
-        Ok(lines)
+        let options = Options::new(align, columns, trim, bias, keep_spaces);
+        Ok(layout(self, &options)?.into_rendered(keep_spaces))
     }
 }
 
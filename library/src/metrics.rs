@@ -0,0 +1,104 @@
+use crate::width::Metric;
+use crate::Options;
+
+/// Measurements of a block of text, see [`measure()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextMetrics {
+    /// The width of the widest line, as used for alignment (see [`display_width()`](crate::display_width())).
+    pub width: usize,
+    /// The number of lines.
+    pub height: usize,
+    /// The index of the first widest line, `None` if there are no lines.
+    pub longest_line_index: Option<usize>,
+    /// Whether any line contains a tab.
+    pub has_tabs: bool,
+    /// Whether any line contains an ANSI escape sequence.
+    pub has_ansi: bool,
+}
+
+/// Measures the lines, using the same width rules as alignment with the default options (see [`measure_with()`]).
+///
+/// # Example
+/// ```
+/// use align_text::measure;
+///
+/// let lines = vec!["Hello".to_string(), "big World!".to_string(), "\t!".to_string()];
+/// let metrics = measure(&lines);
+///
+/// assert_eq!(metrics.width, 10);
+/// assert_eq!(metrics.height, 3);
+/// assert_eq!(metrics.longest_line_index, Some(1));
+/// assert!(metrics.has_tabs);
+/// assert!(!metrics.has_ansi);
+/// ```
+pub fn measure(lines: &[String]) -> TextMetrics {
+    measure_with(lines, &Options::default())
+}
+
+/// Like [`measure()`], but measures the lines like [`layout()`](crate::layout()) with the `options`:
+/// with their [`Options::emoji_width`] and [`Options::width_unit`].
+///
+/// # Example
+/// ```
+/// use align_text::{measure_with, Options, WidthUnit};
+///
+/// let lines = vec!["ab".to_string(), "éé".to_string()];
+/// let options = Options {
+///     width_unit: WidthUnit::Bytes,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(measure_with(&lines, &options).width, 4);
+/// assert_eq!(measure_with(&lines, &options).longest_line_index, Some(1));
+/// ```
+pub fn measure_with(lines: &[String], options: &Options) -> TextMetrics {
+    let metric = Metric::of(options);
+    let mut metrics = TextMetrics {
+        height: lines.len(),
+        ..Default::default()
+    };
+
+    for (index, line) in lines.iter().enumerate() {
+        let width = metric.width(line);
+        if metrics.longest_line_index.is_none() || width > metrics.width {
+            metrics.width = width;
+            metrics.longest_line_index = Some(index);
+        }
+
+        metrics.has_tabs |= line.contains('\t');
+        metrics.has_ansi |= line.contains('\x1b');
+    }
+
+    metrics
+}
+
+/// The width which `percentile` percent of the lines fit in (the nearest rank), measured like [`measure()`],
+/// to choose the columns without letting a few very long lines widen the whole block. 0 if there are no lines.
+/// See [`width_percentile_with()`] to measure them with other options.
+///
+/// # Example
+/// ```
+/// use align_text::width_percentile;
+///
+/// let mut lines = vec!["short".to_string(); 19];
+/// lines.push("a pathological line, much longer than the others".to_string());
+///
+/// assert_eq!(width_percentile(&lines, 95), 5);
+/// assert_eq!(width_percentile(&lines, 100), 48);
+/// ```
+pub fn width_percentile(lines: &[String], percentile: u8) -> usize {
+    width_percentile_with(lines, percentile, &Options::default())
+}
+
+/// Like [`width_percentile()`], but measures the lines like [`measure_with()`].
+pub fn width_percentile_with(lines: &[String], percentile: u8, options: &Options) -> usize {
+    let metric = Metric::of(options);
+    let mut widths = lines
+        .iter()
+        .map(|line| metric.width(line))
+        .collect::<Vec<usize>>();
+    widths.sort_unstable();
+
+    let rank = (widths.len() * usize::from(percentile.min(100))).div_ceil(100);
+    widths.get(rank.saturating_sub(1)).copied().unwrap_or(0)
+}
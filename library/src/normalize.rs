@@ -0,0 +1,49 @@
+use unicode_normalization::{is_nfc, is_nfkc, UnicodeNormalization};
+
+/// The Unicode normalization form [`normalize_unicode()`] puts the text in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// The characters are composed (e.g. `e` followed by a combining acute accent becomes `é`).
+    #[default]
+    Nfc,
+    /// The characters are composed, and the compatibility ones are replaced by their plain equivalents
+    /// (e.g. the ligature `ﬁ` by `fi`, and the full-width `Ａ` by `A`), which can change their width.
+    Nfkc,
+}
+
+/// Normalizes the lines to a Unicode normalization form, so that the same text measures and renders the same
+/// whether its accents were typed precomposed or as combining characters, e.g. when it comes from different sources.
+///
+/// # Example
+/// ```
+/// use align_text::{layout, normalize_unicode, NormalizationForm, Options, Where};
+///
+/// // "café" with a combining accent, and with a precomposed one
+/// let lines = vec!["cafe\u{301}".to_string(), "café".to_string()];
+/// let normalized = normalize_unicode(&lines, NormalizationForm::Nfc);
+/// assert_eq!(normalized, vec!["café", "café"]);
+///
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(6),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     layout(&normalized, &options).unwrap().render(false),
+///     vec!["  café", "  café"]
+/// );
+///
+/// let ligature = vec!["ﬁne".to_string()];
+/// assert_eq!(normalize_unicode(&ligature, NormalizationForm::Nfkc), vec!["fine"]);
+/// ```
+pub fn normalize_unicode(lines: &[String], form: NormalizationForm) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| match form {
+            NormalizationForm::Nfc if is_nfc(line) => line.clone(),
+            NormalizationForm::Nfc => line.nfc().collect(),
+            NormalizationForm::Nfkc if is_nfkc(line) => line.clone(),
+            NormalizationForm::Nfkc => line.nfkc().collect(),
+        })
+        .collect()
+}
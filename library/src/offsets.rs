@@ -0,0 +1,171 @@
+use crate::{Layout, LineLayout};
+
+/// Where an input line ended up after aligning, see [`Layout::offsets()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineOffsets {
+    /// The index of the input line.
+    pub line_index: usize,
+    /// Number of spaces inserted before it (on the first output line it's on).
+    pub before: usize,
+    /// Number of spaces inserted after it (on the last output line it's on), dropped unless the spaces on the right are kept.
+    pub after: usize,
+    /// For each character of the input line, the index of the output line and the position (in characters)
+    /// it's at now, `None` if it was removed (e.g. a trimmed space).
+    pub positions: Vec<Option<(usize, usize)>>,
+}
+
+impl Layout {
+    /// Tells where each of the `input` lines (those which were aligned) ended up, e.g. for editors to move
+    /// their cursors and diagnostics to the same characters after aligning.
+    /// The characters are followed through trimming, squeezing, and wrapping, with the [`LineLayout::range`]
+    /// each output line shows: the characters added (e.g. a hyphen or a wrap marker) aren't taken for the input's.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, LineOffsets, Options, Overflow, Where};
+    ///
+    /// let input = vec![" ab".to_string(), "cd ef".to_string()];
+    /// let options = Options {
+    ///     align: Where::Right,
+    ///     columns: Some(4),
+    ///     overflow: Overflow::WordWrap,
+    ///     trim: true,
+    ///     ..Default::default()
+    /// };
+    /// let layout = layout(&input, &options).unwrap();
+    /// assert_eq!(layout.render(false), vec!["  ab", "  cd", "  ef"]);
+    ///
+    /// let offsets = layout.offsets(&input);
+    /// assert_eq!(
+    ///     offsets[0],
+    ///     LineOffsets {
+    ///         line_index: 0,
+    ///         before: 2,
+    ///         after: 0,
+    ///         positions: vec![None, Some((0, 2)), Some((0, 3))],
+    ///     }
+    /// );
+    /// // the second line was wrapped
+    /// assert_eq!(
+    ///     offsets[1].positions,
+    ///     vec![Some((1, 2)), Some((1, 3)), None, Some((2, 2)), Some((2, 3))]
+    /// );
+    /// ```
+    ///
+    /// * The hyphens added when splitting words aren't those of the input:
+    /// ```
+    /// use align_text::{layout, Options, Overflow, WordSplitter};
+    ///
+    /// let input = vec!["abcdefgh-ij".to_string()];
+    /// let options = Options {
+    ///     columns: Some(5),
+    ///     overflow: Overflow::WordWrap,
+    ///     word_splitter: WordSplitter::Hyphenate,
+    ///     ..Default::default()
+    /// };
+    /// let layout = layout(&input, &options).unwrap();
+    /// assert_eq!(layout.render(false), vec!["abcd-", "efgh-", "-ij"]);
+    ///
+    /// let positions = &layout.offsets(&input)[0].positions;
+    /// assert_eq!(positions[4..9], [Some((1, 0)), Some((1, 1)), Some((1, 2)), Some((1, 3)), Some((2, 0))]);
+    /// assert_eq!(layout.origins(&input), vec![(0, 0), (0, 4), (0, 8)]);
+    /// ```
+    pub fn offsets(&self, input: &[String]) -> Vec<LineOffsets> {
+        input
+            .iter()
+            .enumerate()
+            .map(|(line_index, line)| {
+                let outputs = self
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, output)| output.origin == line_index)
+                    .collect::<Vec<(usize, &LineLayout)>>();
+                // the blank lines added around the text take the origin of a line next to them
+                let content = outputs
+                    .iter()
+                    .filter(|(_, output)| !output.text.is_empty())
+                    .collect::<Vec<&(usize, &LineLayout)>>();
+                let (first, last) = match (content.first(), content.last()) {
+                    (Some(first), Some(last)) => (Some(first.1), Some(last.1)),
+                    _ => (outputs.first().map(|o| o.1), outputs.last().map(|o| o.1)),
+                };
+
+                LineOffsets {
+                    line_index,
+                    before: first.map_or(0, |output| output.before),
+                    after: last.map_or(0, |output| output.after),
+                    positions: positions(line, &content),
+                }
+            })
+            .collect()
+    }
+
+    /// For each output line, the index of the input line it comes from, and the position (in characters) in it
+    /// of its first character, e.g. to attribute the pieces of the wrapped lines to their source.
+    /// The lines without any character of the input (e.g. blank lines) are at position 0.
+    ///
+    /// The `input` lines are those which were aligned, see [`LineLayout::range`].
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Options, Overflow};
+    ///
+    /// let input = vec!["ab cd".to_string(), "e".to_string()];
+    /// let options = Options {
+    ///     columns: Some(2),
+    ///     overflow: Overflow::WordWrap,
+    ///     ..Default::default()
+    /// };
+    /// let layout = layout(&input, &options).unwrap();
+    /// assert_eq!(layout.render(false), vec!["ab", "cd", "e"]);
+    /// assert_eq!(layout.origins(&input), vec![(0, 0), (0, 3), (1, 0)]);
+    /// ```
+    pub fn origins(&self, input: &[String]) -> Vec<(usize, usize)> {
+        self.lines
+            .iter()
+            .map(|line| {
+                let start = input
+                    .get(line.origin)
+                    .filter(|_| !line.range.is_empty())
+                    .and_then(|input| input.get(..line.range.start))
+                    .map_or(0, |before| before.chars().count());
+                (line.origin, start)
+            })
+            .collect()
+    }
+}
+
+/// Matches the characters of the input line with those of its output lines: the part of the line each one shows
+/// (see [`LineLayout::range`]) is found as it is in its content, or else (e.g. when its white-spaces were changed)
+/// character by character, skipping those which were removed or added.
+fn positions(line: &str, outputs: &[&(usize, &LineLayout)]) -> Vec<Option<(usize, usize)>> {
+    let mut positions = vec![None; line.chars().count()];
+
+    for (index, output) in outputs {
+        let Some(shown) = line.get(output.range.clone()) else {
+            continue;
+        };
+        let first = line[..output.range.start].chars().count();
+        let chars = shown.chars().collect::<Vec<char>>();
+
+        if let Some(found) = output.text.find(shown).filter(|_| !shown.is_empty()) {
+            let position = output.before + output.text[..found].chars().count();
+            for offset in 0..chars.len() {
+                positions[first + offset] = Some((*index, position + offset));
+            }
+            continue;
+        }
+
+        let mut next = 0;
+        for (position, ch) in output.text.chars().enumerate() {
+            // an added character (e.g. a hyphen) isn't in the rest of the range
+            if let Some(found) = chars[next..].iter().position(|&input| input == ch) {
+                positions[first + next + found] = Some((*index, output.before + position));
+                next += found + 1;
+            }
+        }
+    }
+
+    positions
+}
@@ -0,0 +1,134 @@
+use crate::width;
+
+/// The operators aligned by [`align_operators()`] in code: assignments, mappings, and fields.
+pub const ASSIGNMENT_OPERATORS: [&str; 4] = [":=", "=>", "=", ":"];
+
+/// Aligns the first operator of consecutive lines (among `operators`) to the same column, the way many code styles do,
+/// with one space around each operator, except `:` which stays after the text before it (`key:   value`).
+/// Lines without any operator are left as they are, and separate the blocks which are aligned together.
+///
+/// Operators inside strings (quoted with `"` or `'`) and comments (after `//` or `#`) aren't matched,
+/// neither are comparisons (`==`, `<=`, ...) and paths (`::`). At the same position, the longest operator wins.
+///
+/// # Example
+/// ```
+/// use align_text::{align_operators, ASSIGNMENT_OPERATORS};
+///
+/// let lines = [
+///     "let x = 1;",
+///     "let long_name = \"a = b\";",
+///     "",
+///     "key: value",
+///     "other_key: 2",
+/// ]
+/// .map(String::from);
+///
+/// assert_eq!(
+///     align_operators(&lines, &ASSIGNMENT_OPERATORS),
+///     vec![
+///         "let x         = 1;",
+///         "let long_name = \"a = b\";",
+///         "",
+///         "key:       value",
+///         "other_key: 2",
+///     ]
+/// );
+/// ```
+pub fn align_operators(lines: &[String], operators: &[&str]) -> Vec<String> {
+    let mut operators = operators.to_vec();
+    operators.sort_by_key(|operator| std::cmp::Reverse(operator.len()));
+
+    let found = lines
+        .iter()
+        .map(|line| find_operator(line, &operators))
+        .collect::<Vec<Option<(usize, &str)>>>();
+
+    let mut result = Vec::with_capacity(lines.len());
+    let mut start = 0;
+    while start < lines.len() {
+        // the block of consecutive lines with an operator, or a line without one
+        let end = match found[start] {
+            None => start + 1,
+            Some(_) => found[start..]
+                .iter()
+                .position(Option::is_none)
+                .map_or(lines.len(), |length| start + length),
+        };
+
+        let left_width = (start..end)
+            .filter_map(|index| found[index].map(|(at, _)| lines[index][..at].trim_end()))
+            .map(width::display_width)
+            .max()
+            .unwrap_or(0);
+
+        for index in start..end {
+            let line = &lines[index];
+            result.push(match found[index] {
+                None => line.clone(),
+                Some((at, operator)) => {
+                    let left = line[..at].trim_end();
+                    let right = line[at + operator.len()..].trim_start();
+                    let padding = " ".repeat(left_width - width::display_width(left));
+                    let aligned = if operator == ":" {
+                        // like punctuation, after the key
+                        format!("{left}{operator}{padding} {right}")
+                    } else {
+                        format!("{left}{padding} {operator} {right}")
+                    };
+                    aligned.trim_end().to_string()
+                }
+            });
+        }
+
+        start = end;
+    }
+
+    result
+}
+
+/// The byte index of the first operator in the line, outside strings and comments.
+fn find_operator<'a>(line: &str, operators: &[&'a str]) -> Option<(usize, &'a str)> {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (index, ch) in line.char_indices() {
+        if let Some(q) = quote {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                _ if ch == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+
+        let rest = &line[index..];
+        if ch == '"' || ch == '\'' {
+            quote = Some(ch);
+        } else if rest.starts_with("//") || ch == '#' {
+            return None;
+        } else if let Some(operator) = operators
+            .iter()
+            .find(|operator| rest.starts_with(**operator) && is_operator(line, index, operator))
+        {
+            return Some((index, operator));
+        }
+    }
+
+    None
+}
+
+/// Whether the operator at `index` isn't part of a longer one, like `==` or `::`.
+fn is_operator(line: &str, index: usize, operator: &str) -> bool {
+    let before = line[..index].chars().next_back();
+    let after = line[index + operator.len()..].chars().next();
+
+    match operator {
+        "=" => {
+            !matches!(before, Some('=' | '!' | '<' | '>' | ':'))
+                && !matches!(after, Some('=' | '>'))
+        }
+        ":" => before != Some(':') && !matches!(after, Some(':' | '=')),
+        _ => true,
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt::{Alignment, Formatter};
+
+use crate::width::Metric;
+use crate::{Bias, EmojiWidth, VWhere, Where, WidthUnit};
+
+/// Matches URLs, for [`Options::unbreakable`].
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Options, Overflow, URL_PATTERN};
+///
+/// let lines = vec!["see https://example.com/a/b for more".to_string()];
+/// let options = Options {
+///     columns: Some(12),
+///     overflow: Overflow::Wrap,
+///     unbreakable: vec![URL_PATTERN.to_string()],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     layout(&lines, &options).unwrap().render(false),
+///     vec!["see ", "https://example.com/a/b", " for more"]
+/// );
+/// ```
+pub const URL_PATTERN: &str = r"\b[A-Za-z][A-Za-z0-9+.-]*://\S+";
+
+/// Matches file paths (words containing a slash), for [`Options::unbreakable`].
+pub const PATH_PATTERN: &str = r"\S*/\S*";
+
+/// Match markdown's code spans, links and images, and emphasized text, for [`Options::unbreakable`].
+pub const MARKDOWN_PATTERNS: [&str; 5] = [
+    r"`[^`]*`",
+    r"!?\[[^\]]*\]\([^)]*\)",
+    r"(\*\*|__)[^*_]+(\*\*|__)",
+    r"\*[^*\s][^*]*\*",
+    r"\b_[^_\s][^_]*_\b",
+];
+
+/// What to do with lines which are wider than the number of columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Fail with [`Error::InsufficientColumns`](crate::Error::InsufficientColumns).
+    #[default]
+    Error,
+    /// Split them into several lines.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Options, Overflow};
+    ///
+    /// let lines = vec!["a long message".to_string()];
+    /// let options = Options {
+    ///     columns: Some(8),
+    ///     overflow: Overflow::Wrap,
+    ///     subsequent_prefix: "│ ".to_string(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     layout(&lines, &options).unwrap().render(false),
+    ///     vec!["a long m", "│ essage"]
+    /// );
+    /// ```
+    Wrap,
+    /// Split them into several lines between words, only breaking the words which are wider than the columns
+    /// (see [`WordSplitter`]).
+    /// The lines which fit are left as they are.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Options, Overflow};
+    ///
+    /// let lines = vec!["see the documentation".to_string(), "ok".to_string()];
+    /// let options = Options {
+    ///     columns: Some(8),
+    ///     overflow: Overflow::WordWrap,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     layout(&lines, &options).unwrap().render(false),
+    ///     vec!["see the", "document", "ation", "ok"]
+    /// );
+    /// ```
+    WordWrap,
+}
+
+/// How the words which are wider than the columns are split with [`Overflow::WordWrap`].
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Options, Overflow, WordSplitter};
+///
+/// let lines = vec!["a documentation".to_string()];
+/// let options = |word_splitter| Options {
+///     columns: Some(6),
+///     overflow: Overflow::WordWrap,
+///     word_splitter,
+///     ..Default::default()
+/// };
+/// let wrap = |word_splitter| layout(&lines, &options(word_splitter)).unwrap().render(false);
+///
+/// assert_eq!(wrap(WordSplitter::Never), vec!["a", "documentation"]);
+/// assert_eq!(wrap(WordSplitter::AtWidth), vec!["a", "docume", "ntatio", "n"]);
+/// assert_eq!(wrap(WordSplitter::Hyphenate), vec!["a", "docum-", "entat-", "ion"]);
+///
+/// // the continuations need room for their prefix, a character, and the hyphen
+/// let prefixed = |columns| Options {
+///     columns: Some(columns),
+///     subsequent_prefix: "> ".to_string(),
+///     ..options(WordSplitter::Hyphenate)
+/// };
+/// let lines = vec!["abcdefgh".to_string()];
+/// assert_eq!(
+///     layout(&lines, &prefixed(4)).unwrap().render(false),
+///     vec!["abc-", "> d-", "> e-", "> f-", "> gh"]
+/// );
+/// assert!(layout(&lines, &prefixed(3)).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WordSplitter {
+    /// Leave them whole, wider than the columns.
+    Never,
+    /// Split them at the number of columns.
+    #[default]
+    AtWidth,
+    /// Split them one column before, and end each piece with a hyphen.
+    Hyphenate,
+}
+
+/// The alignment options, as taken by [`layout()`](crate::layout()).
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Bias, Options, Overflow, Where};
+///
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(12),
+///     overflow: Overflow::Wrap,
+///     ..Default::default()
+/// };
+/// assert_eq!(options, Options::new(Where::Right, Some((12, true)), false, Bias::Left, false));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Where to align the lines.
+    pub align: Where,
+    /// Number of columns, `None` to use the text's width (maximum line width).
+    pub columns: Option<usize>,
+    /// What to do with lines which don't fit in the columns.
+    pub overflow: Overflow,
+    /// How to split the words which don't fit in the columns, with [`Overflow::WordWrap`].
+    pub word_splitter: WordSplitter,
+    /// Inserted at the start of the remainders of wrapped lines (e.g. `"> "` to carry over quoting),
+    /// and counted in their width.
+    /// Wrapping fails with [`Error::InsufficientColumns`](crate::Error::InsufficientColumns) if it leaves no room
+    /// for their text.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Error, Options, Overflow, URL_PATTERN};
+    ///
+    /// let lines = vec!["quoted".to_string()];
+    /// let mut options = Options {
+    ///     columns: Some(4),
+    ///     overflow: Overflow::Wrap,
+    ///     subsequent_prefix: "> ".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     layout(&lines, &options).unwrap().render(false),
+    ///     vec!["quot", "> ed"]
+    /// );
+    ///
+    /// options.columns = Some(2);
+    /// assert!(matches!(
+    ///     layout(&lines, &options),
+    ///     Err(Error::InsufficientColumns { available: 2, .. })
+    /// ));
+    ///
+    /// // an unbreakable token goes whole after the prefix, even if it's too wide
+    /// options.columns = Some(5);
+    /// options.unbreakable = vec![URL_PATTERN.to_string()];
+    /// assert_eq!(
+    ///     layout(&["ab http://x.y/z".to_string()], &options).unwrap().render(false),
+    ///     vec!["ab ", "> http://x.y/z"]
+    /// );
+    /// ```
+    pub subsequent_prefix: String,
+    /// Appended to the pieces of wrapped lines which continue on the next line (e.g. `"↩"` or `"\\"`),
+    /// and counted in their width, to tell the wrapping from the line breaks of the text.
+    /// Wrapping fails with [`Error::InsufficientColumns`](crate::Error::InsufficientColumns) if it leaves no room
+    /// for their text.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, Error, Options, Overflow};
+    ///
+    /// let lines = vec!["wrapped line".to_string(), "short".to_string()];
+    /// let mut options = Options {
+    ///     columns: Some(8),
+    ///     overflow: Overflow::WordWrap,
+    ///     wrap_marker: "↩".to_string(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     layout(&lines, &options).unwrap().render(false),
+    ///     vec!["wrapped↩", "line", "short"]
+    /// );
+    ///
+    /// options.columns = Some(1);
+    /// assert!(matches!(
+    ///     layout(&lines, &options),
+    ///     Err(Error::InsufficientColumns { available: 1, .. })
+    /// ));
+    /// ```
+    pub wrap_marker: String,
+    /// Where to align the remainders of wrapped lines, `None` to align them like the other lines.
+    pub remainder: Option<Where>,
+    /// Where to align the last line of each paragraph (lines separated by blank lines), `None` to align it like the other lines.
+    pub last_line: Option<Where>,
+    /// Regular expressions of the tokens which mustn't be broken when wrapping (e.g. [`URL_PATTERN`]):
+    /// they're moved whole to the next line, or left wider than the columns if they don't fit on a line of their own.
+    pub unbreakable: Vec<String>,
+    /// Before erroring or wrapping, squeeze the runs of white-spaces inside the lines which don't fit.
+    pub shrink: bool,
+    /// Squeeze the runs of white-spaces inside all the lines, before aligment.
+    pub squeeze: bool,
+    /// Expand the tabs into spaces, up to the next multiple of this number of columns, before aligment.
+    pub tab_width: Option<usize>,
+    /// Remove the leading white-spaces the lines have in common before aligment, see [`dedent()`](crate::dedent).
+    /// It's done after expanding the tabs and before trimming.
+    pub dedent: bool,
+    /// Whether to trim white-spaces around the lines before aligment.
+    pub trim: bool,
+    /// Which side to bias towards if line can't be perfectly centered.
+    pub bias: Bias,
+    /// Whether to keep the spaces on the right.
+    pub keep_spaces: bool,
+    /// Number of rows, `None` to use the text's height (number of lines).
+    pub rows: Option<usize>,
+    /// Where to align the lines vertically within the rows.
+    pub valign: VWhere,
+    /// Split the lines into pages of `rows` lines when there are too many, instead of failing with
+    /// [`Error::InsufficientRows`](crate::Error::InsufficientRows).
+    pub paginate: bool,
+    /// How many columns the emoji take up when measuring the lines, see [`Options::width_of()`].
+    pub emoji_width: EmojiWidth,
+    /// What the width of the lines is counted in when measuring, wrapping, and padding them.
+    pub width_unit: WidthUnit,
+}
+
+impl Options {
+    /// Options from the params of [`Align::align_text()`](crate::Align::align_text).
+    pub fn new(
+        align: Where,
+        columns: Option<(usize, bool)>,
+        trim: bool,
+        bias: Bias,
+        keep_spaces: bool,
+    ) -> Options {
+        Options {
+            align,
+            columns: columns.map(|(num, _)| num),
+            overflow: match columns {
+                Some((_, true)) => Overflow::Wrap,
+                _ => Overflow::Error,
+            },
+            word_splitter: WordSplitter::AtWidth,
+            subsequent_prefix: String::new(),
+            wrap_marker: String::new(),
+            remainder: None,
+            last_line: None,
+            unbreakable: vec![],
+            shrink: false,
+            squeeze: false,
+            tab_width: None,
+            dedent: false,
+            trim,
+            bias,
+            keep_spaces,
+            rows: None,
+            valign: VWhere::Top,
+            paginate: false,
+            emoji_width: EmojiWidth::Unicode,
+            width_unit: WidthUnit::Columns,
+        }
+    }
+
+    /// The width of the text as it's measured when aligning the lines: like [`display_width()`](crate::display_width),
+    /// the emoji taking up [`Options::emoji_width`] columns, and in [`Options::width_unit`].
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{layout, EmojiWidth, Options, Where};
+    ///
+    /// let lines = vec!["👩‍💻".to_string(), "abcd".to_string()];
+    /// let options = Options {
+    ///     align: Where::Center,
+    ///     emoji_width: EmojiWidth::Narrow,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(options.width_of(&lines[0]), 1);
+    /// assert_eq!(layout(&lines, &options).unwrap().render(false), vec![" 👩‍💻", "abcd"]);
+    /// ```
+    pub fn width_of(&self, text: &str) -> usize {
+        Metric::of(self).width(text)
+    }
+
+    /// Options from the width and alignment of a format spec, e.g. `{:^30}` to center within 30 columns,
+    /// left when it's not specified (like strings).
+    /// See [`Aligned`](crate::Aligned) to format text with them.
+    pub fn from_formatter(f: &Formatter<'_>) -> Options {
+        Options {
+            align: match f.align() {
+                None | Some(Alignment::Left) => Where::Left,
+                Some(Alignment::Center) => Where::Center,
+                Some(Alignment::Right) => Where::Right,
+            },
+            columns: f.width(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::width;
+
+/// Places the `top` block on the `base` block, with its first line at `row` and its first column at `column`,
+/// and returns the combined block.
+/// The base is extended with blank lines and spaces where the top block goes past it.
+///
+/// # Params
+/// * `transparent`: Whether the spaces of the top block let the base show through, instead of overwriting it.
+///
+/// # Note
+/// A wide character of the base which is half covered is replaced with a space.
+///
+/// # Example
+/// ```
+/// use align_text::overlay;
+///
+/// let base = ["+--------+", "|        |", "+--------+"].map(String::from);
+/// let top = ["OK"].map(String::from);
+/// assert_eq!(
+///     overlay(&base, &top, 1, 4, false),
+///     vec!["+--------+", "|   OK   |", "+--------+"]
+/// );
+///
+/// let top = ["--  --"].map(String::from);
+/// assert_eq!(overlay(&base, &top, 2, 2, true)[2], "+--------+");
+/// assert_eq!(overlay(&base, &top, 3, 12, true)[3], "            --  --");
+/// ```
+pub fn overlay(
+    base: &[String],
+    top: &[String],
+    row: usize,
+    column: usize,
+    transparent: bool,
+) -> Vec<String> {
+    let mut result = base.to_vec();
+    if result.len() < row + top.len() {
+        result.resize(row + top.len(), String::new());
+    }
+
+    for (line, top_line) in result[row..].iter_mut().zip(top) {
+        let mut line_cells = cells(line);
+        let top_cells = cells(top_line);
+
+        if line_cells.len() < column + top_cells.len() {
+            line_cells.resize(column + top_cells.len(), " ".to_string());
+        }
+        for (cell, top_cell) in line_cells[column..].iter_mut().zip(top_cells) {
+            if !(transparent && top_cell == " ") {
+                *cell = top_cell;
+            }
+        }
+
+        *line = join(line_cells);
+    }
+
+    result
+}
+
+/// Splits a line into the content of each of its columns.
+/// A wide character is followed by an empty cell, and zero-width characters join the previous cell.
+fn cells(line: &str) -> Vec<String> {
+    let mut cells = Vec::<String>::new();
+
+    for ch in line.chars() {
+        match (ch.width().unwrap_or(0), cells.last_mut()) {
+            (0, Some(cell)) => cell.push(ch),
+            (2, _) => {
+                cells.push(ch.to_string());
+                cells.push(String::new());
+            }
+            _ => cells.push(ch.to_string()),
+        }
+    }
+
+    cells
+}
+
+/// Joins the cells back into a line, replacing the wide characters which lost one of their halves with spaces.
+fn join(mut cells: Vec<String>) -> String {
+    for i in 0..cells.len() {
+        let wide = width::display_width(&cells[i]) == 2;
+        let followed = cells.get(i + 1).is_some_and(|next| next.is_empty());
+        let preceded = i > 0 && width::display_width(&cells[i - 1]) == 2;
+
+        if (wide && !followed) || (cells[i].is_empty() && !preceded) {
+            cells[i] = " ".to_string();
+        }
+    }
+
+    cells.concat()
+}
@@ -0,0 +1,159 @@
+use std::str::FromStr;
+
+use crate::{layout, Bias, EmojiWidth, Error, Layout, LineLayout, Options, Where, WidthUnit};
+
+/// A single operation in a [`Pipeline`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Trim the white-spaces around each line.
+    Trim,
+    /// Wrap the lines which are longer than the given number of columns.
+    Wrap(usize),
+    /// Align the lines within the block's own width.
+    Inner(Where),
+    /// Align the block within a number of columns (text's width if `None`).
+    Outer(Where, Option<usize>),
+}
+
+impl FromStr for Step {
+    type Err = String;
+
+    /// Parses a step written as `name[:arg[:arg]]`, e.g. `trim`, `wrap:60`, `inner:left`, `outer:center:80`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let parts = input.trim().split(':').collect::<Vec<&str>>();
+
+        let columns = |arg: &str| {
+            arg.parse::<usize>()
+                .map_err(|_| format!("invalid number of columns '{arg}'"))
+        };
+
+        match parts.as_slice() {
+            ["trim"] => Ok(Step::Trim),
+            ["wrap", num] => Ok(Step::Wrap(columns(num)?)),
+            ["inner", wh] => Ok(Step::Inner(wh.parse()?)),
+            ["outer", wh] => Ok(Step::Outer(wh.parse()?, None)),
+            ["outer", wh, num] => Ok(Step::Outer(wh.parse()?, Some(columns(num)?))),
+            _ => Err(format!("invalid pipeline step '{}'", input.trim())),
+        }
+    }
+}
+
+/// A sequence of [`Step`]s applied in order to a block of text,
+/// instead of calling [`Align::align_text()`](crate::Align::align_text) several times by hand.
+///
+/// # Example
+/// ```
+/// use align_text::{Pipeline, Step, Where};
+///
+/// let pipeline: Pipeline = "trim | inner:right | outer:center:12".parse().unwrap();
+/// assert_eq!(pipeline.steps[2], Step::Outer(Where::Center, Some(12)));
+///
+/// let lines = vec!["  Hello ".to_string(), "World!".to_string()];
+/// let aligned = pipeline.run(&lines).unwrap();
+/// assert_eq!(aligned, vec!["    Hello", "   World!"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    pub steps: Vec<Step>,
+    /// Which side to bias towards if a line can't be perfectly centered.
+    pub bias: Bias,
+    /// Whether to keep the spaces on the right in the final output.
+    pub keep_spaces: bool,
+    /// How many columns the emoji take up, see [`Options::emoji_width`].
+    pub emoji_width: EmojiWidth,
+    /// What the width of the lines is counted in, see [`Options::width_unit`].
+    pub width_unit: WidthUnit,
+}
+
+impl FromStr for Pipeline {
+    type Err = String;
+
+    /// Parses steps separated by `|`, see [`Step::from_str()`].
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let steps = input
+            .split('|')
+            .map(|step| step.parse())
+            .collect::<Result<Vec<Step>, String>>()?;
+
+        Ok(Pipeline {
+            steps,
+            ..Default::default()
+        })
+    }
+}
+
+impl Pipeline {
+    /// Applies each step to the lines, in order.
+    /// See [`Error`] for potential errors returned.
+    pub fn run(&self, lines: &[String]) -> Result<Vec<String>, Error> {
+        Ok(self.layout(lines)?.into_rendered(self.keep_spaces))
+    }
+
+    /// Computes how the lines are placed after all the steps, see [`Pipeline::run()`].
+    ///
+    /// The spaces inserted by the alignment steps are counted as padding,
+    /// until a step changes the text around them (trimming or wrapping), after which they're part of the content.
+    pub fn layout(&self, lines: &[String]) -> Result<Layout, Error> {
+        let mut result = Layout::from(lines.to_vec());
+
+        for step in &self.steps {
+            let (align, columns, trim) = match step {
+                Step::Trim => (Where::Left, None, true),
+                Step::Wrap(num) => (Where::Left, Some((*num, true)), false),
+                Step::Inner(wh) => (wh.clone(), None, false),
+                Step::Outer(wh, num) => (wh.clone(), num.map(|num| (num, false)), false),
+            };
+            let pads = matches!(step, Step::Inner(_) | Step::Outer(..));
+
+            let current = result.render(true);
+            let options = Options {
+                emoji_width: self.emoji_width,
+                width_unit: self.width_unit,
+                ..Options::new(align, columns, trim, self.bias, true)
+            };
+            // the line's index in the input, not in the previous step's output
+            let step_layout = layout(&current, &options)
+                .map_err(|e| e.map_line_index(|index| result.lines[index].origin))?;
+
+            let lines = step_layout
+                .lines
+                .into_iter()
+                .map(|line| {
+                    let previous = &result.lines[line.origin];
+                    if pads {
+                        // the text didn't change, add the new spaces to the previous ones
+                        LineLayout {
+                            before: previous.before + line.before,
+                            after: previous.after + line.after,
+                            ..previous.clone()
+                        }
+                    } else {
+                        // the range in the previous step's line, which has its spaces before it
+                        let at = |index: usize| {
+                            (previous.range.start + index.saturating_sub(previous.before))
+                                .min(previous.range.end)
+                        };
+                        LineLayout {
+                            origin: previous.origin,
+                            range: at(line.range.start)..at(line.range.end),
+                            before: 0,
+                            after: 0,
+                            ..line
+                        }
+                    }
+                })
+                .collect();
+
+            result = Layout {
+                columns: step_layout.columns,
+                lines,
+            };
+        }
+
+        if !self.keep_spaces {
+            result.trim_end();
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,255 @@
+use std::ops::Range;
+
+use crate::ansi::Styles;
+use crate::layout::{compile, find_all};
+use crate::width::Metric;
+use crate::{width, Bias, Error, Layout, LineAligner, LineLayout, Options, Overflow, Where};
+
+/// The alignment decisions of [`layout()`](crate::layout()), without the text: see [`plan()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// The number of columns the lines are aligned in.
+    pub columns: usize,
+    /// The output lines, in order.
+    pub lines: Vec<PlannedLine>,
+    align: Where,
+    bias: Bias,
+    metric: Metric,
+}
+
+/// An output line of a [`Plan`]: a part of an input line, and the spaces around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedLine {
+    /// The index of the input line it comes from.
+    pub origin: usize,
+    /// The byte range of the input line it shows, without the trimmed spaces, nor the spaces dropped at a wrap.
+    pub range: Range<usize>,
+    /// Whether a hyphen is added after it, see [`WordSplitter::Hyphenate`](crate::WordSplitter::Hyphenate).
+    pub hyphen: bool,
+    /// The number of columns it takes up, with the hyphen.
+    pub width: usize,
+    /// Number of spaces before it.
+    pub before: usize,
+    /// Number of spaces after it.
+    pub after: usize,
+}
+
+/// Decides how the lines are aligned, like [`layout()`](crate::layout()) but without building any string:
+/// the number of columns, where the lines are wrapped, and the spaces around each piece.
+/// The plan can be inspected or changed (e.g. with [`Plan::keep_whole()`]) before [`Plan::apply()`] builds the lines.
+///
+/// Only the options which keep the text as it is are supported: those which change it
+/// ([`Options::squeeze`], [`Options::shrink`], [`Options::subsequent_prefix`], [`Options::wrap_marker`],
+/// [`Options::tab_width`], [`Options::dedent`]),
+/// realign some of the lines ([`Options::remainder`], [`Options::last_line`]), or add blank lines ([`Options::rows`])
+/// fail with [`Error::UnsupportedOption`].
+///
+/// # Example
+/// ```
+/// use align_text::{layout, plan, Options, Overflow, Where};
+///
+/// let lines = vec!["see".to_string(), "https://example.com".to_string(), "for more".to_string()];
+/// let options = Options {
+///     align: Where::Center,
+///     columns: Some(10),
+///     overflow: Overflow::WordWrap,
+///     ..Default::default()
+/// };
+///
+/// let mut plan = plan(&lines, &options).unwrap();
+/// assert_eq!(plan.columns, 10);
+/// assert_eq!(plan.lines.len(), 4);
+/// assert_eq!((plan.lines[0].before, plan.lines[0].after), (3, 4));
+/// assert_eq!(plan.apply(&lines), layout(&lines, &options).unwrap());
+///
+/// // the URL isn't wrapped after all, the columns widen to fit it
+/// plan.keep_whole(&lines, 1);
+/// assert_eq!(
+///     plan.apply(&lines).render(false),
+///     vec!["        see", "https://example.com", "     for more"]
+/// );
+/// ```
+pub fn plan(lines: &[String], options: &Options) -> Result<Plan, Error> {
+    let unsupported = [
+        ("squeeze", options.squeeze),
+        ("shrink", options.shrink),
+        ("subsequent_prefix", !options.subsequent_prefix.is_empty()),
+        ("wrap_marker", !options.wrap_marker.is_empty()),
+        ("tab_width", options.tab_width.is_some()),
+        ("dedent", options.dedent),
+        ("remainder", options.remainder.is_some()),
+        ("last_line", options.last_line.is_some()),
+        ("rows", options.rows.is_some()),
+    ];
+    if let Some((option, _)) = unsupported.iter().find(|(_, set)| *set) {
+        return Err(Error::UnsupportedOption(option));
+    }
+
+    let mut plan = Plan {
+        columns: options.columns.unwrap_or(0),
+        lines: vec![],
+        align: options.align.clone(),
+        bias: options.bias,
+        metric: Metric::of(options),
+    };
+    if lines.is_empty() {
+        return Ok(plan);
+    }
+
+    let ranges = lines
+        .iter()
+        .map(|line| match options.trim {
+            true => {
+                let end = line.trim_end().len();
+                (line.len() - line.trim_start().len()).min(end)..end
+            }
+            false => 0..line.len(),
+        })
+        .collect::<Vec<Range<usize>>>();
+    let widths = lines
+        .iter()
+        .zip(&ranges)
+        .map(|(line, range)| plan.metric.width(&line[range.clone()]))
+        .collect::<Vec<usize>>();
+    let text_width = widths.iter().copied().max().unwrap_or_default();
+
+    plan.columns = match options.columns {
+        None => text_width,
+        Some(num) if num < text_width => {
+            if options.overflow == Overflow::Error {
+                return Err(Error::InsufficientColumns {
+                    required: text_width,
+                    available: num,
+                    line_index: widths.iter().position(|&w| w == text_width).unwrap_or(0),
+                });
+            }
+
+            let unbreakable = compile(&options.unbreakable)?;
+            for (origin, (line, range)) in lines.iter().zip(&ranges).enumerate() {
+                let text = &line[range.clone()];
+                let pieces = width::pieces(
+                    text,
+                    num,
+                    &find_all(&unbreakable, text),
+                    options.overflow,
+                    options.word_splitter,
+                    plan.metric,
+                );
+                for piece in pieces {
+                    let range = range.start + piece.range.start..range.start + piece.range.end;
+                    let width = plan.metric.width(&line[range.clone()]) + usize::from(piece.hyphen);
+                    plan.lines.push(PlannedLine {
+                        origin,
+                        range,
+                        hyphen: piece.hyphen,
+                        width,
+                        before: 0,
+                        after: 0,
+                    });
+                }
+            }
+            plan.pad();
+            return Ok(plan);
+        }
+        Some(num) => num,
+    };
+
+    plan.lines = ranges
+        .into_iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(origin, (range, width))| PlannedLine {
+            origin,
+            range,
+            hyphen: false,
+            width,
+            before: 0,
+            after: 0,
+        })
+        .collect();
+    plan.pad();
+    Ok(plan)
+}
+
+impl Plan {
+    /// Builds the lines of the plan out of the `lines` it was made for.
+    /// The styles (e.g. colors) open at the start of a piece are opened again, and those open at its end closed.
+    pub fn apply(&self, lines: &[String]) -> Layout {
+        let lines = self
+            .lines
+            .iter()
+            .map(|planned| {
+                let line = &lines[planned.origin];
+                let hyphen = if planned.hyphen { "-" } else { "" };
+                let text = if line.contains('\x1b') {
+                    let mut styles = Styles::default();
+                    styles.update(&line[..planned.range.start]);
+                    let opening = styles.opening();
+                    styles.update(&line[planned.range.clone()]);
+                    format!(
+                        "{opening}{}{hyphen}{}",
+                        &line[planned.range.clone()],
+                        styles.closing()
+                    )
+                } else {
+                    format!("{}{hyphen}", &line[planned.range.clone()])
+                };
+
+                LineLayout {
+                    text,
+                    origin: planned.origin,
+                    range: planned.range.clone(),
+                    before: planned.before,
+                    after: planned.after,
+                }
+            })
+            .collect();
+
+        Layout {
+            columns: self.columns,
+            lines,
+        }
+    }
+
+    /// Joins the pieces of the input line `origin` back into one line, e.g. to not wrap a line after all.
+    /// The columns widen if it doesn't fit in them, and the lines are aligned again.
+    pub fn keep_whole(&mut self, lines: &[String], origin: usize) {
+        let Some(first) = self.lines.iter().position(|line| line.origin == origin) else {
+            return;
+        };
+        let count = self.lines[first..]
+            .iter()
+            .take_while(|line| line.origin == origin)
+            .count();
+        let range = self.lines[first].range.start..self.lines[first + count - 1].range.end;
+        let width = self.metric.width(&lines[origin][range.clone()]);
+
+        self.lines.splice(
+            first..first + count,
+            [PlannedLine {
+                origin,
+                range,
+                hyphen: false,
+                width,
+                before: 0,
+                after: 0,
+            }],
+        );
+        self.columns = self.columns.max(width);
+        self.pad();
+    }
+
+    /// Puts the spaces around the lines to fill the columns, according to the alignment.
+    fn pad(&mut self) {
+        let mut odd_lines = 0;
+        for line in self.lines.iter_mut() {
+            let space = self.columns.saturating_sub(line.width);
+            let bias = self.bias.for_line(space, &mut odd_lines);
+            line.before = self
+                .align
+                .spaces_before("", line.origin, space, bias)
+                .min(space);
+            line.after = space - line.before;
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::str::FromStr;
+
+use crate::width::{self, Metric};
+use crate::{Bias, Diagnostic, EmojiWidth, Error, Where, WidthUnit};
+
+/// What to do with a value which is wider than its field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Truncate {
+    /// Cut it at the width of the field.
+    #[default]
+    Cut,
+    /// Cut it and end it with `…`.
+    Ellipsis,
+    /// Fail with [`Error::InsufficientColumns`].
+    Error,
+}
+
+/// A field of a [`RecordLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordField {
+    /// The exact number of columns of the field.
+    pub width: usize,
+    /// Where to align the value within the field.
+    pub align: Where,
+    /// What to do when the value is wider than the field.
+    pub truncate: Truncate,
+}
+
+/// The fields of fixed-width records (like mainframe or COBOL exports), each with an exact width and its own alignment.
+///
+/// Records are split into the fields' columns, or at the `delimiter` if there's one,
+/// then each value is trimmed, and aligned within its field.
+/// The text after the last field is kept as it is (it's added to the last field with a delimiter).
+///
+/// It's parsed from comma-separated `WIDTH[:ALIGN[:TRUNCATE]]` fields, `ALIGN` being `left` (the default), `center`,
+/// or `right`, and `TRUNCATE` being `cut` (the default), `ellipsis`, or `error`.
+///
+/// # Example
+/// ```
+/// use align_text::{RecordLayout, WidthUnit};
+///
+/// let mut layout: RecordLayout = "6:left,5:right,8:center:ellipsis".parse().unwrap();
+/// let records = vec!["ab    12   xyz".to_string(), "cdefgh7       ab".to_string()];
+///
+/// assert_eq!(
+///     layout.format(&records).unwrap(),
+///     vec!["ab       12  xyz   ", "cdefgh    7   ab   "]
+/// );
+///
+/// layout.delimiter = Some(';');
+/// let records = vec!["ab;12;xyz".to_string(), "ab;12;a long one".to_string()];
+/// assert_eq!(
+///     layout.format(&records).unwrap(),
+///     vec!["ab       12  xyz   ", "ab       12a long …"]
+/// );
+///
+/// // the widths can be counted in bytes
+/// let mut layout: RecordLayout = "3,4:right".parse().unwrap();
+/// layout.width_unit = WidthUnit::Bytes;
+/// assert_eq!(layout.format(&["éab".to_string()]).unwrap(), vec!["éa   b"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordLayout {
+    pub fields: Vec<RecordField>,
+    /// The character separating the values of the input records, `None` if they're fixed-width too.
+    pub delimiter: Option<char>,
+    /// How wide the emoji are, see [`Options::emoji_width`](crate::Options::emoji_width).
+    pub emoji_width: EmojiWidth,
+    /// What the widths are counted in, see [`Options::width_unit`](crate::Options::width_unit).
+    pub width_unit: WidthUnit,
+}
+
+impl FromStr for RecordLayout {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let fields = input
+            .split(',')
+            .map(|field| {
+                let invalid =
+                    || format!("invalid field '{field}', expected WIDTH[:ALIGN[:TRUNCATE]]");
+                let mut parts = field.trim().split(':');
+
+                let width = parts
+                    .next()
+                    .and_then(|width| width.parse().ok())
+                    .ok_or_else(invalid)?;
+                let align = match parts.next() {
+                    None => Where::Left,
+                    Some(align) => align.parse().map_err(|_| invalid())?,
+                };
+                let truncate = match parts.next() {
+                    None | Some("cut") => Truncate::Cut,
+                    Some("ellipsis") => Truncate::Ellipsis,
+                    Some("error") => Truncate::Error,
+                    Some(_) => return Err(invalid()),
+                };
+                if parts.next().is_some() {
+                    return Err(invalid());
+                }
+
+                Ok(RecordField {
+                    width,
+                    align,
+                    truncate,
+                })
+            })
+            .collect::<Result<Vec<RecordField>, String>>()?;
+
+        Ok(RecordLayout {
+            fields,
+            delimiter: None,
+            emoji_width: EmojiWidth::default(),
+            width_unit: WidthUnit::default(),
+        })
+    }
+}
+
+impl RecordLayout {
+    /// Formats one record.
+    /// Fails with [`Error::InsufficientColumns`] (with a `line_index` of 0) if a value is too wide for a field
+    /// whose policy is [`Truncate::Error`].
+    pub fn format_record(&self, record: &str) -> Result<String, Error> {
+        self.format_fields(record, 0, &mut vec![])
+    }
+
+    /// Formats each record, see [`RecordLayout::format_record()`].
+    pub fn format(&self, records: &[String]) -> Result<Vec<String>, Error> {
+        Ok(self.format_with_diagnostics(records)?.0)
+    }
+
+    /// Like [`RecordLayout::format()`], but also returns a [`Diagnostic::Truncated`] for each value which was cut.
+    ///
+    /// # Example
+    /// ```
+    /// use align_text::{Diagnostic, RecordLayout};
+    ///
+    /// let mut layout: RecordLayout = "4,3:right".parse().unwrap();
+    /// layout.delimiter = Some(';');
+    /// let records = vec!["ab;1".to_string(), "abcdef;2".to_string()];
+    ///
+    /// let (formatted, diagnostics) = layout.format_with_diagnostics(&records).unwrap();
+    /// assert_eq!(formatted, vec!["ab    1", "abcd  2"]);
+    /// assert_eq!(
+    ///     diagnostics,
+    ///     vec![Diagnostic::Truncated { line_index: 1, width: 6, available: 4 }]
+    /// );
+    /// ```
+    pub fn format_with_diagnostics(
+        &self,
+        records: &[String],
+    ) -> Result<(Vec<String>, Vec<Diagnostic>), Error> {
+        let mut diagnostics = vec![];
+        let formatted = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| self.format_fields(record, index, &mut diagnostics))
+            .collect::<Result<Vec<String>, Error>>()?;
+
+        Ok((formatted, diagnostics))
+    }
+
+    /// Formats a record, the diagnostics and errors having `line_index`.
+    fn format_fields(
+        &self,
+        record: &str,
+        line_index: usize,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<String, Error> {
+        let values = self.values(record);
+        let mut result = String::new();
+
+        for (field, value) in self.fields.iter().zip(&values) {
+            let value = value.trim();
+            let value_width = self.metric().width(value);
+            let value = if value_width <= field.width {
+                value.to_string()
+            } else {
+                let ellipsis = match field.truncate {
+                    Truncate::Cut => "",
+                    Truncate::Ellipsis => "…",
+                    Truncate::Error => {
+                        return Err(Error::InsufficientColumns {
+                            required: value_width,
+                            available: field.width,
+                            line_index,
+                        })
+                    }
+                };
+                diagnostics.push(Diagnostic::Truncated {
+                    line_index,
+                    width: value_width,
+                    available: field.width,
+                });
+                width::truncate(value, field.width, ellipsis, self.metric())
+            };
+            result.push_str(&width::pad(
+                &value,
+                field.width,
+                &field.align,
+                Bias::Left,
+                self.metric(),
+            ));
+        }
+
+        // the text after the fields
+        if let Some(rest) = values.get(self.fields.len()) {
+            result.push_str(rest);
+        }
+
+        Ok(result)
+    }
+
+    /// How the values are measured.
+    fn metric(&self) -> Metric {
+        Metric::new(self.emoji_width, self.width_unit)
+    }
+
+    /// The value of each field, followed by the text after them, if there's any.
+    fn values(&self, record: &str) -> Vec<String> {
+        let mut values = vec![];
+
+        match self.delimiter {
+            Some(delimiter) => {
+                let mut parts = record.split(delimiter);
+                for index in 0..self.fields.len() {
+                    let value = if index + 1 == self.fields.len() {
+                        parts
+                            .by_ref()
+                            .collect::<Vec<&str>>()
+                            .join(&delimiter.to_string())
+                    } else {
+                        parts.next().unwrap_or_default().to_string()
+                    };
+                    values.push(value);
+                }
+            }
+            None => {
+                let mut rest = record.to_string();
+                for field in &self.fields {
+                    let (_, value, after) =
+                        width::split_columns(&rest, 0, field.width, self.metric());
+                    values.push(value);
+                    rest = after;
+                }
+                if !rest.is_empty() {
+                    values.push(rest);
+                }
+            }
+        }
+
+        values
+    }
+}
@@ -0,0 +1,95 @@
+use std::ops::Range;
+
+use crate::width::{self, Metric};
+use crate::{layout, Error, Options};
+
+/// Aligns only a rectangular region of the lines, leaving the text around it untouched,
+/// like aligning a selection in a text editor.
+/// See [`Error`] for potential errors returned.
+///
+/// # Params
+/// * `rows`: The indices of the lines in the region.
+/// * `columns`: The columns of the region, in terminal columns (see [`display_width()`](crate::display_width)).
+/// * `options`: How to align the region's content, where `columns` is the region's width after aligning
+///   (`None` to keep its current width), the text on its right moves accordingly.
+///   The lines which don't reach the region are left as they are.
+///   With [`Overflow::Wrap`](crate::Overflow::Wrap), the remainders are inserted below their lines,
+///   and `rows` is ignored.
+///
+/// # Example
+/// ```
+/// use align_text::{align_region, Options, Where};
+///
+/// let lines = ["a |x    | z", "b |yy   | z", "c"].map(String::from);
+/// let options = Options {
+///     align: Where::Right,
+///     trim: true,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     align_region(&lines, 0..3, 3..8, &options).unwrap(),
+///     vec!["a |    x| z", "b |   yy| z", "c"]
+/// );
+/// ```
+pub fn align_region(
+    lines: &[String],
+    rows: Range<usize>,
+    columns: Range<usize>,
+    options: &Options,
+) -> Result<Vec<String>, Error> {
+    let start = rows.start.min(lines.len());
+    let end = rows.end.clamp(start, lines.len());
+
+    let parts = lines[start..end]
+        .iter()
+        .map(|line| width::split_columns(line, columns.start, columns.end, Metric::of(options)))
+        .collect::<Vec<(String, String, String)>>();
+    let texts = parts
+        .iter()
+        .map(|(_, region, _)| region.clone())
+        .collect::<Vec<String>>();
+
+    let region_options = Options {
+        columns: Some(options.columns.unwrap_or(columns.len())),
+        keep_spaces: true,
+        rows: None,
+        ..options.clone()
+    };
+    let layout =
+        layout(&texts, &region_options).map_err(|e| e.map_line_index(|index| start + index))?;
+
+    let mut aligned = lines[..start].to_vec();
+    let mut previous = None;
+
+    for line in &layout.lines {
+        let row = &lines[start + line.origin];
+        let (prefix, _, suffix) = &parts[line.origin];
+
+        let remainder = previous.replace(line.origin) == Some(line.origin);
+
+        let text = if remainder {
+            // the remainder of a wrapped region, below its line
+            format!(
+                "{}{}",
+                " ".repeat(options.width_of(prefix)),
+                line.render(true)
+            )
+        } else if options.width_of(row) <= columns.start {
+            aligned.push(row.clone());
+            continue;
+        } else {
+            format!("{prefix}{}{suffix}", line.render(true))
+        };
+
+        aligned.push(if options.keep_spaces {
+            text
+        } else {
+            text.trim_end().to_string()
+        });
+    }
+
+    aligned.extend_from_slice(&lines[end..]);
+
+    Ok(aligned)
+}
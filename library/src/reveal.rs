@@ -0,0 +1,112 @@
+use crate::ansi;
+
+/// How [`reveal()`] shows the control characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placeholders {
+    /// In caret notation, like `cat -v`: `^M` for a carriage return, `^?` for DEL, and `M-^[` for the C1 controls.
+    #[default]
+    Caret,
+    /// As their Unicode control pictures, e.g. `␍` for a carriage return, in a single column.
+    /// The C1 controls, which don't have one, are in caret notation.
+    Pictures,
+}
+
+/// Replaces the characters which can't be seen with visible placeholders, so that the text aligns
+/// and shows why it looked misaligned: the control characters (tabs included) according to `placeholders`,
+/// and the no-break spaces with `·`. The escape sequences (e.g. colors) are left as they are.
+///
+/// # Example
+/// ```
+/// use align_text::{reveal, Placeholders};
+///
+/// let lines = vec!["total\r".to_string(), "a\u{a0}b\tc\x1b[1mbold\x1b[0m".to_string()];
+///
+/// assert_eq!(
+///     reveal(&lines, Placeholders::Caret),
+///     vec!["total^M", "a·b^Ic\x1b[1mbold\x1b[0m"]
+/// );
+/// assert_eq!(
+///     reveal(&lines, Placeholders::Pictures),
+///     vec!["total␍", "a·b␉c\x1b[1mbold\x1b[0m"]
+/// );
+/// ```
+pub fn reveal(lines: &[String], placeholders: Placeholders) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            let mut revealed = String::with_capacity(line.len());
+            let mut index = 0;
+            while let Some(ch) = line[index..].chars().next() {
+                if let Some(len) = ansi::escape_len(&line[index..]) {
+                    revealed.push_str(&line[index..index + len]);
+                    index += len;
+                    continue;
+                }
+
+                match (ch, placeholders) {
+                    ('\u{a0}' | '\u{202f}', _) => revealed.push('·'),
+                    ('\0'..='\x1f', Placeholders::Pictures) => {
+                        revealed.extend(char::from_u32(0x2400 + ch as u32));
+                    }
+                    ('\x7f', Placeholders::Pictures) => revealed.push('␡'),
+                    ('\0'..='\x1f', _) => {
+                        revealed.push('^');
+                        revealed.push((ch as u8 + 0x40) as char);
+                    }
+                    ('\x7f', _) => revealed.push_str("^?"),
+                    ('\u{80}'..='\u{9f}', _) => {
+                        revealed.push_str("M-^");
+                        revealed.push((ch as u8 - 0x40) as char);
+                    }
+                    _ => revealed.push(ch),
+                }
+                index += ch.len_utf8();
+            }
+            revealed
+        })
+        .collect()
+}
+
+/// Escapes the characters which aren't printable ASCII like Rust's debug formatting (`\t`, `\r`, `\n`,
+/// or `\u{…}` with the hexadecimal code point), so that the lines are pure ASCII, one column per byte.
+/// The escape sequences (e.g. colors) are escaped too.
+///
+/// # Example
+/// ```
+/// use align_text::{escape_non_ascii, layout, Options, Where};
+///
+/// let lines = vec!["café\t1".to_string(), "日本".to_string()];
+/// let escaped = escape_non_ascii(&lines);
+/// assert_eq!(escaped, vec!["caf\\u{e9}\\t1", "\\u{65e5}\\u{672c}"]);
+///
+/// let options = Options {
+///     align: Where::Right,
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     layout(&escaped, &options).unwrap().render(false),
+///     vec!["    caf\\u{e9}\\t1", "\\u{65e5}\\u{672c}"]
+/// );
+/// ```
+pub fn escape_non_ascii(lines: &[String]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            if line.bytes().all(|byte| (b' '..=b'~').contains(&byte)) {
+                return line.clone();
+            }
+
+            let mut escaped = String::with_capacity(line.len());
+            for ch in line.chars() {
+                match ch {
+                    ' '..='~' => escaped.push(ch),
+                    '\t' => escaped.push_str("\\t"),
+                    '\r' => escaped.push_str("\\r"),
+                    '\n' => escaped.push_str("\\n"),
+                    _ => escaped.push_str(&format!("\\u{{{:x}}}", ch as u32)),
+                }
+            }
+            escaped
+        })
+        .collect()
+}
@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use ropey::{Rope, RopeSlice};
+
+use crate::{layout, Error, LineLayout, Options};
+
+/// Where to insert the spaces of a line and what to remove from it, in chars from the start of the rope.
+struct Edit {
+    start: usize,
+    /// The leading and trailing spaces removed by trimming.
+    leading: usize,
+    trailing: Range<usize>,
+    before: usize,
+    after: usize,
+}
+
+/// Aligns the `lines` of a rope (a range of line indices) in place, like [`layout()`], for text editors' buffers.
+///
+/// When no line needs to wrap, the lines are only measured (without copying them unless they span several chunks),
+/// and the spaces are inserted and removed in place, keeping their line endings.
+/// Otherwise, the lines are replaced by their aligned version, joined with `\n` (the last one keeping its line ending).
+///
+/// # Example
+/// ```
+/// use align_text::{align_rope, layout, Bias, Options, Overflow, Where};
+/// use ropey::Rope;
+///
+/// let mut rope = Rope::from_str("title\n  a  \nlonger line\r\nnot aligned\n");
+/// let options = Options {
+///     align: Where::Center,
+///     trim: true,
+///     ..Default::default()
+/// };
+///
+/// align_rope(&mut rope, 0..3, &options).unwrap();
+/// assert_eq!(rope.to_string(), "   title\n     a\nlonger line\r\nnot aligned\n");
+///
+/// // the longer line wraps
+/// let options = Options {
+///     columns: Some(8),
+///     overflow: Overflow::Wrap,
+///     ..options
+/// };
+/// align_rope(&mut rope, 1..3, &options).unwrap();
+/// assert_eq!(rope.to_string(), "   title\n   a\nlonger l\n  ine\r\nnot aligned\n");
+///
+/// // like the layout, the alternate bias leans to the other side on every other line which can't be centered
+/// let lines = ["ab", "cd", "longest"];
+/// let mut rope = Rope::from_str(&lines.join("\n"));
+/// let options = Options {
+///     align: Where::Center,
+///     bias: Bias::Alternate,
+///     ..Default::default()
+/// };
+/// align_rope(&mut rope, 0..3, &options).unwrap();
+/// let aligned = layout(&lines.map(String::from), &options).unwrap().render(false);
+/// assert_eq!(rope.to_string(), aligned.join("\n"));
+/// assert_eq!(rope.to_string(), "  ab\n   cd\nlongest");
+/// ```
+pub fn align_rope(rope: &mut Rope, lines: Range<usize>, options: &Options) -> Result<(), Error> {
+    let lines = lines.start.min(rope.len_lines())..lines.end.min(rope.len_lines());
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    match edits(rope, lines.clone(), options) {
+        Some(edits) => {
+            // from the end, so the earlier positions stay valid
+            for edit in edits.into_iter().rev() {
+                rope.remove(edit.trailing.clone());
+                rope.insert(edit.trailing.start, &" ".repeat(edit.after));
+                rope.remove(edit.start..edit.start + edit.leading);
+                rope.insert(edit.start, &" ".repeat(edit.before));
+            }
+        }
+        None => {
+            let texts = lines
+                .clone()
+                .map(|index| content(rope.line(index)).to_string())
+                .collect::<Vec<String>>();
+            let aligned = layout(&texts, options)?.into_rendered(options.keep_spaces);
+
+            let start = rope.line_to_char(lines.start);
+            let last = rope.line(lines.end - 1);
+            let end = rope.line_to_char(lines.end - 1) + content(last).len_chars();
+            rope.remove(start..end);
+            rope.insert(start, &aligned.join("\n"));
+        }
+    }
+
+    Ok(())
+}
+
+/// The edits aligning each line, if they can be made in place: if no line wraps, and the options don't change the text.
+fn edits(rope: &Rope, lines: Range<usize>, options: &Options) -> Option<Vec<Edit>> {
+    if options.squeeze
+        || options.shrink
+        || options.tab_width.is_some()
+        || options.dedent
+        || options.rows.is_some()
+        || options.remainder.is_some()
+        || options.last_line.is_some()
+    {
+        return None;
+    }
+
+    let mut measured = vec![];
+    for index in lines {
+        let line = content(rope.line(index));
+        // only the lines spanning several chunks are copied
+        let text = match line.as_str() {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(line.to_string()),
+        };
+        let (leading, trailing) = if options.trim {
+            let leading = text.chars().take_while(|ch| ch.is_whitespace()).count();
+            let trailing = text[text.trim_end().len()..].chars().count();
+            (leading, trailing)
+        } else {
+            (0, 0)
+        };
+        let visible = if options.trim { text.trim() } else { &text };
+
+        let start = rope.line_to_char(index);
+        let end = start + line.len_chars();
+        // a blank line is all leading spaces
+        let trailing = (end - trailing).max(start + leading)..end;
+        measured.push((start, leading, trailing, options.width_of(visible)));
+    }
+
+    let text_width = measured.iter().map(|(.., width)| *width).max()?;
+    let columns = options.columns.unwrap_or(text_width);
+    if text_width > columns {
+        return None;
+    }
+
+    let mut odd_lines = 0;
+    let edits = measured
+        .into_iter()
+        .map(|(start, leading, trailing, width)| {
+            let mut line = LineLayout {
+                text: String::new(),
+                origin: 0,
+                range: 0..0,
+                before: 0,
+                after: columns - width,
+            };
+            let bias = options.bias.for_line(columns - width, &mut odd_lines);
+            line.realign(&options.align, bias);
+            Edit {
+                start,
+                leading,
+                trailing,
+                before: line.before,
+                after: if options.keep_spaces { line.after } else { 0 },
+            }
+        })
+        .collect();
+
+    Some(edits)
+}
+
+/// The line without its line ending.
+fn content(line: RopeSlice) -> RopeSlice {
+    let mut end = line.len_chars();
+    if end > 0 && is_line_ending(line.char(end - 1)) {
+        end -= 1;
+        if end > 0 && line.char(end) == '\n' && line.char(end - 1) == '\r' {
+            end -= 1;
+        }
+    }
+    line.slice(..end)
+}
+
+fn is_line_ending(ch: char) -> bool {
+    matches!(
+        ch,
+        '\n' | '\r' | '\u{0b}' | '\u{0c}' | '\u{85}' | '\u{2028}' | '\u{2029}'
+    )
+}
@@ -0,0 +1,62 @@
+use crate::Error;
+
+/// The characters which end a line but which [`str::lines()`] doesn't split on:
+/// a lone carriage return, U+2028 LINE SEPARATOR, and U+2029 PARAGRAPH SEPARATOR.
+pub const LINE_SEPARATORS: [char; 3] = ['\r', '\u{2028}', '\u{2029}'];
+
+/// What to do with the [`LINE_SEPARATORS`] left inside the lines, which would throw off their widths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineSeparators {
+    /// Split the lines at them.
+    #[default]
+    Split,
+    /// Remove them.
+    Strip,
+    /// Fail with [`Error::StraySeparator`].
+    Error,
+}
+
+/// Handles the [`LINE_SEPARATORS`] inside the lines according to `separators`.
+///
+/// # Example
+/// ```
+/// use align_text::{normalize_separators, Error, LineSeparators};
+///
+/// let lines = vec!["one\rtwo".to_string(), "three\u{2028}four".to_string()];
+///
+/// assert_eq!(
+///     normalize_separators(&lines, LineSeparators::Split).unwrap(),
+///     vec!["one", "two", "three", "four"]
+/// );
+/// assert_eq!(
+///     normalize_separators(&lines, LineSeparators::Strip).unwrap(),
+///     vec!["onetwo", "threefour"]
+/// );
+/// assert_eq!(
+///     normalize_separators(&lines, LineSeparators::Error),
+///     Err(Error::StraySeparator { line_index: 0 })
+/// );
+/// ```
+pub fn normalize_separators(
+    lines: &[String],
+    separators: LineSeparators,
+) -> Result<Vec<String>, Error> {
+    let mut normalized = Vec::with_capacity(lines.len());
+
+    for (index, line) in lines.iter().enumerate() {
+        if !line.contains(LINE_SEPARATORS) {
+            normalized.push(line.clone());
+            continue;
+        }
+
+        match separators {
+            LineSeparators::Split => {
+                normalized.extend(line.split(LINE_SEPARATORS).map(str::to_string));
+            }
+            LineSeparators::Strip => normalized.push(line.replace(LINE_SEPARATORS, "")),
+            LineSeparators::Error => return Err(Error::StraySeparator { line_index: index }),
+        }
+    }
+
+    Ok(normalized)
+}
@@ -0,0 +1,164 @@
+use crate::width::Metric;
+use crate::{format::is_number, width, Bias, Where};
+
+/// A type whose fields are rendered as the columns of a table, see [`table()`].
+///
+/// With the `derive` feature, it can be derived with `#[derive(AlignRow)]`,
+/// where `#[align(...)]` attributes on the fields choose their columns' alignment and width.
+pub trait AlignRow {
+    /// Where to align each column (right if it only has numbers and left otherwise, if `None`),
+    /// and its width (the widest cell's if `None`).
+    fn columns() -> Vec<(Option<Where>, Option<usize>)>;
+
+    /// The header of each column.
+    fn headers() -> Vec<String>;
+
+    /// The content of each column.
+    fn cells(&self) -> Vec<String>;
+}
+
+/// Renders the rows as a table: each cell is padded to the width of its column, and the columns are separated by a space.
+/// The columns without an alignment are aligned right if they only contain numbers, left otherwise.
+/// `headers`: Whether to start with a line of the columns' headers.
+///
+/// Cells which are wider than their column's set width are left as they are.
+///
+/// # Example
+/// ```
+/// use align_text::{table, AlignRow, Where};
+///
+/// struct Item(&'static str, f32);
+///
+/// impl AlignRow for Item {
+///     fn columns() -> Vec<(Option<Where>, Option<usize>)> {
+///         vec![(Some(Where::Left), None), (None, Some(7))]
+///     }
+///     fn headers() -> Vec<String> {
+///         vec!["item".to_string(), "price".to_string()]
+///     }
+///     fn cells(&self) -> Vec<String> {
+///         vec![self.0.to_string(), format!("{:.2}", self.1)]
+///     }
+/// }
+///
+/// let items = [Item("coffee", 2.5), Item("croissant", 1.2)];
+/// assert_eq!(
+///     table(&items, false),
+///     vec!["coffee       2.50", "croissant    1.20"]
+/// );
+/// ```
+pub fn table<T: AlignRow>(rows: &[T], headers: bool) -> Vec<String> {
+    let mut lines = rows
+        .iter()
+        .map(|row| row.cells())
+        .collect::<Vec<Vec<String>>>();
+
+    // whether each column only has numbers, before adding the headers
+    let numeric = |index: usize| {
+        !lines.is_empty()
+            && lines
+                .iter()
+                .all(|cells| cells.get(index).is_some_and(|cell| is_number(cell)))
+    };
+    let aligns = T::columns()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (align, _))| match align {
+            Some(align) => align,
+            None if numeric(index) => Where::Right,
+            None => Where::Left,
+        })
+        .collect::<Vec<Where>>();
+
+    if headers {
+        lines.insert(0, T::headers());
+    }
+
+    let widths = column_widths(&lines);
+    let columns = T::columns()
+        .into_iter()
+        .zip(aligns)
+        .enumerate()
+        .map(|(index, ((_, columns), align))| {
+            let columns = columns.unwrap_or_else(|| widths.get(index).copied().unwrap_or(0));
+            (align, columns)
+        })
+        .collect::<Vec<(Where, usize)>>();
+
+    lines
+        .iter()
+        .map(|cells| {
+            cells
+                .iter()
+                .zip(&columns)
+                .map(|(cell, (align, columns))| {
+                    width::pad(cell, *columns, align, Bias::Left, Metric::default())
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// The width of each column of the rows of cells: that of its widest cell.
+/// The rows can have different numbers of cells.
+///
+/// # Example
+/// ```
+/// use align_text::column_widths;
+///
+/// let rows = vec![
+///     vec!["name".to_string(), "qty".to_string()],
+///     vec!["croissant".to_string(), "12".to_string(), "fresh".to_string()],
+/// ];
+/// assert_eq!(column_widths(&rows), vec![9, 3, 5]);
+/// ```
+pub fn column_widths(rows: &[Vec<String>]) -> Vec<usize> {
+    let mut widths = vec![];
+    for cells in rows {
+        if widths.len() < cells.len() {
+            widths.resize(cells.len(), 0);
+        }
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(width::display_width(cell));
+        }
+    }
+    widths
+}
+
+/// Pads each cell of the rows to the width of its column (see [`column_widths()`]), according to the column's alignment
+/// in `aligns` (left for the columns without one), and returns the rows of padded cells.
+/// No separator is imposed: the cells can be joined with spaces, `│`, or put in a grid of the application's own.
+///
+/// # Example
+/// ```
+/// use align_text::{align_cells, Bias, Where};
+///
+/// let rows = vec![
+///     vec!["item".to_string(), "price".to_string()],
+///     vec!["croissant".to_string(), "1.20".to_string()],
+/// ];
+/// let aligned = align_cells(&rows, &[Where::Center, Where::Right], Bias::Left);
+///
+/// assert_eq!(aligned[0], vec!["  item   ", "price"]);
+/// assert_eq!(aligned[1], vec!["croissant", " 1.20"]);
+/// assert_eq!(aligned[1].join(" | "), "croissant |  1.20");
+/// ```
+pub fn align_cells(rows: &[Vec<String>], aligns: &[Where], bias: Bias) -> Vec<Vec<String>> {
+    let widths = column_widths(rows);
+
+    rows.iter()
+        .map(|cells| {
+            cells
+                .iter()
+                .enumerate()
+                .map(|(index, cell)| {
+                    let align = aligns.get(index).unwrap_or(&Where::Left);
+                    width::pad(cell, widths[index], align, bias, Metric::default())
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,74 @@
+use regex::Regex;
+
+use crate::width;
+
+/// Timestamps recognized at the start of the lines, optionally in brackets:
+/// ISO 8601 (`2024-03-01T12:00:00.123Z`, `2024-03-01 12:00:00`), and syslog (`Mar  1 12:00:00`).
+pub const TIMESTAMP_PATTERN: &str = concat!(
+    r"^\[?(?:",
+    r"\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?",
+    r"|[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}(?:\.\d+)?",
+    r")\]?"
+);
+
+/// Pads the lines starting with a timestamp so that their messages start at the same column,
+/// one space after the longest timestamp, even when the timestamps' widths vary (e.g. with and without milliseconds).
+/// See [`TIMESTAMP_PATTERN`] for the recognized timestamps, the other lines are left as they are.
+///
+/// # Example
+/// ```
+/// use align_text::align_timestamps;
+///
+/// let lines = [
+///     "2024-03-01T12:00:00Z server started",
+///     "2024-03-01T12:00:01.250Z  listening on :80",
+///     "  at main.rs:12",
+/// ]
+/// .map(String::from);
+///
+/// assert_eq!(
+///     align_timestamps(&lines),
+///     vec![
+///         "2024-03-01T12:00:00Z     server started",
+///         "2024-03-01T12:00:01.250Z listening on :80",
+///         "  at main.rs:12",
+///     ]
+/// );
+/// ```
+pub fn align_timestamps(lines: &[String]) -> Vec<String> {
+    let regex = Regex::new(TIMESTAMP_PATTERN).expect("the timestamp pattern is valid");
+
+    // the timestamp and the message of each line starting with one
+    let split = lines
+        .iter()
+        .map(|line| {
+            let found = regex.find(line)?;
+            let message = &line[found.end()..];
+            // a timestamp is followed by a space or ends the line
+            if !message.is_empty() && !message.starts_with(char::is_whitespace) {
+                return None;
+            }
+            Some((found.as_str(), message.trim_start()))
+        })
+        .collect::<Vec<Option<(&str, &str)>>>();
+
+    let column = split
+        .iter()
+        .flatten()
+        .map(|(timestamp, _)| width::display_width(timestamp) + 1)
+        .max()
+        .unwrap_or(0);
+
+    split
+        .iter()
+        .zip(lines)
+        .map(|(split, line)| match split {
+            None => line.clone(),
+            Some((timestamp, "")) => timestamp.to_string(),
+            Some((timestamp, message)) => {
+                let padding = column - width::display_width(timestamp);
+                format!("{timestamp}{}{message}", " ".repeat(padding))
+            }
+        })
+        .collect()
+}
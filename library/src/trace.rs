@@ -0,0 +1,17 @@
+//! Instrumentation with `tracing`, compiled away without the `tracing` feature.
+
+/// Enters a debug span for the rest of the scope, e.g. `phase!("wrap", lines = 3)`.
+macro_rules! phase {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
+/// Emits a debug event, e.g. `event!(text_width, "measured the lines")`.
+macro_rules! event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
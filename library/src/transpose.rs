@@ -0,0 +1,89 @@
+use crate::ansi;
+use crate::emoji::cluster_len;
+
+/// Which way [`rotate()`] turns the block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    /// A quarter turn clockwise: the first line becomes the last column, read from top to bottom.
+    #[default]
+    Clockwise,
+    /// A quarter turn counterclockwise: the first line becomes the first column, read from bottom to top.
+    Counterclockwise,
+}
+
+/// Swaps the rows and the columns of characters of the block: the first line becomes the first column, and so on.
+/// The lines are padded with spaces to make the block rectangular, so the result is too.
+///
+/// # Note
+/// Each character (or emoji sequence) takes one cell, whatever its width,
+/// and the escape sequences (e.g. colors) are removed.
+///
+/// # Example
+/// ```
+/// use align_text::transpose;
+///
+/// let lines = ["abc", "de"].map(String::from);
+/// assert_eq!(transpose(&lines), vec!["ad", "be", "c "]);
+/// ```
+pub fn transpose(lines: &[String]) -> Vec<String> {
+    let cells = cells(lines);
+    let width = cells.first().map_or(0, Vec::len);
+
+    (0..width)
+        .map(|column| cells.iter().map(|row| row[column]).collect())
+        .collect()
+}
+
+/// Turns the block a quarter turn, e.g. to write a label vertically next to a table.
+/// The lines are padded with spaces to make the block rectangular, as for [`transpose()`].
+///
+/// # Example
+/// ```
+/// use align_text::{rotate, Rotation};
+///
+/// let lines = ["abc", "de"].map(String::from);
+/// assert_eq!(rotate(&lines, Rotation::Clockwise), vec!["da", "eb", " c"]);
+/// assert_eq!(rotate(&lines, Rotation::Counterclockwise), vec!["c ", "be", "ad"]);
+/// ```
+pub fn rotate(lines: &[String], rotation: Rotation) -> Vec<String> {
+    let cells = cells(lines);
+    let width = cells.first().map_or(0, Vec::len);
+    let column = |index: usize| -> String {
+        match rotation {
+            Rotation::Clockwise => cells.iter().rev().map(|row| row[index]).collect(),
+            Rotation::Counterclockwise => cells.iter().map(|row| row[index]).collect(),
+        }
+    };
+
+    match rotation {
+        Rotation::Clockwise => (0..width).map(column).collect(),
+        Rotation::Counterclockwise => (0..width).rev().map(column).collect(),
+    }
+}
+
+/// The characters of the lines, padded with spaces to the same number.
+fn cells(lines: &[String]) -> Vec<Vec<&str>> {
+    let mut cells = lines
+        .iter()
+        .map(|line| {
+            let mut row = vec![];
+            let mut rest = line.as_str();
+            while !rest.is_empty() {
+                if let Some(len) = ansi::escape_len(rest) {
+                    rest = &rest[len..];
+                    continue;
+                }
+                let len = cluster_len(rest);
+                row.push(&rest[..len]);
+                rest = &rest[len..];
+            }
+            row
+        })
+        .collect::<Vec<Vec<&str>>>();
+
+    let width = cells.iter().map(Vec::len).max().unwrap_or(0);
+    for row in cells.iter_mut() {
+        row.resize(width, " ");
+    }
+    cells
+}
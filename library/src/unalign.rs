@@ -0,0 +1,20 @@
+/// Removes the padding added by alignment: strips the runs of `fill` around each line,
+/// which brings the block back to left-aligned, trimmed lines.
+///
+/// # Example
+/// ```
+/// use align_text::{unalign, Align, Bias, Where};
+///
+/// let lines = vec!["Hello".to_string(), "World!".to_string()];
+/// let centered = lines
+///     .align_text(Where::Center, Some((20, false)), false, Bias::Left, true)
+///     .unwrap();
+///
+/// assert_eq!(unalign(&centered, ' '), lines);
+/// ```
+pub fn unalign(lines: &[String], fill: char) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line.trim_matches(fill).to_string())
+        .collect()
+}
@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::ansi::{self, Styles};
+use crate::emoji::{self, cluster_len, EmojiWidth};
+use crate::{Bias, Options, Overflow, Where, WordSplitter};
+
+use unicode_width::UnicodeWidthStr;
+
+/// What the width of the text is counted in, see [`Options::width_unit`].
+///
+/// # Example
+/// ```
+/// use align_text::{layout, Options, Overflow, WidthUnit};
+///
+/// let lines = vec!["née à".to_string()];
+/// let options = Options {
+///     columns: Some(4),
+///     overflow: Overflow::Wrap,
+///     width_unit: WidthUnit::Bytes,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(options.width_of("été"), 5);
+/// assert_eq!(layout(&lines, &options).unwrap().render(false), vec!["née", " à"]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WidthUnit {
+    /// Terminal columns, see [`display_width()`].
+    #[default]
+    Columns,
+    /// Bytes of UTF-8, like `fold -b`, for the systems with fixed-length records.
+    /// The lines are still split between characters, and the escape sequences still don't count.
+    Bytes,
+}
+
+/// The number of terminal columns the text takes up.
+/// Wide characters (e.g. CJK) count as 2, combining and zero-width characters as 0,
+/// and so do ANSI escape sequences (e.g. colors). The emoji take up the columns given by the Unicode standard,
+/// see [`Options::width_of()`](crate::Options::width_of) to measure them like the terminal does,
+/// or to measure the text in bytes instead.
+///
+/// # Example
+/// ```
+/// use align_text::display_width;
+///
+/// assert_eq!(display_width("abc"), 3);
+/// assert_eq!(display_width("été"), 3);
+/// assert_eq!(display_width("日本"), 4);
+/// assert_eq!(display_width("\x1b[31mred\x1b[0m"), 3);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    Metric::default().width(text)
+}
+
+/// How the text is measured, as set in the [`Options`] (see [`Options::emoji_width`] and [`Options::width_unit`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Metric {
+    emoji_width: EmojiWidth,
+    unit: WidthUnit,
+}
+
+impl Metric {
+    pub(crate) fn new(emoji_width: EmojiWidth, unit: WidthUnit) -> Metric {
+        Metric { emoji_width, unit }
+    }
+
+    pub(crate) fn of(options: &Options) -> Metric {
+        Metric::new(options.emoji_width, options.width_unit)
+    }
+
+    /// The width of the text, see [`display_width()`].
+    pub(crate) fn width(self, text: &str) -> usize {
+        let visible = ansi::strip(text);
+        if self.unit == WidthUnit::Bytes {
+            return visible.len();
+        }
+        if self.emoji_width == EmojiWidth::Unicode {
+            return visible.width();
+        }
+
+        let mut width = 0;
+        let mut rest = visible.as_ref();
+        while !rest.is_empty() {
+            let len = cluster_len(rest);
+            width += self.cluster_width(&rest[..len]);
+            rest = &rest[len..];
+        }
+        width
+    }
+
+    /// The width of a character and the characters making an emoji sequence with it.
+    pub(crate) fn cluster_width(self, cluster: &str) -> usize {
+        if self.unit == WidthUnit::Bytes {
+            return cluster.len();
+        }
+        emoji::cluster_width(cluster, self.emoji_width)
+    }
+}
+
+/// Measures lines like [`display_width()`], but the identical lines (e.g. the same reactions all over a chat log)
+/// are only measured once, and the lines of printable ASCII are counted without being measured.
+#[derive(Debug)]
+pub(crate) struct WidthCache<'a> {
+    metric: Metric,
+    widths: HashMap<&'a str, usize>,
+}
+
+impl<'a> WidthCache<'a> {
+    pub(crate) fn new(metric: Metric) -> WidthCache<'a> {
+        WidthCache {
+            metric,
+            widths: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn width(&mut self, text: &'a str) -> usize {
+        if text.bytes().all(|byte| (b' '..=b'~').contains(&byte)) {
+            return text.len();
+        }
+        *self
+            .widths
+            .entry(text)
+            .or_insert_with(|| self.metric.width(text))
+    }
+}
+
+/// The length in bytes of the first character of the text (or emoji sequence), with the escape sequences before it.
+pub(crate) fn unit_len(text: &str) -> usize {
+    let mut index = 0;
+    while let Some(len) = ansi::escape_len(&text[index..]) {
+        index += len;
+    }
+    index + cluster_len(&text[index..])
+}
+
+/// Splits a line into pieces which fit in `columns`, according to `overflow`:
+/// anywhere with [`Overflow::Wrap`], between words with [`Overflow::WordWrap`].
+/// A character wider than `columns` gets a piece to itself, and emoji sequences aren't split.
+///
+/// The line isn't split inside the `unbreakable` byte ranges: they're moved whole to the next piece,
+/// or left wider than `columns` if they start a piece.
+///
+/// Escape sequences are never split, and the styles and hyperlinks open at the end of a piece are closed there,
+/// then opened again at the start of the next one. Each piece comes with its byte range in the line.
+pub(crate) fn split(
+    line: &str,
+    columns: usize,
+    unbreakable: &[Range<usize>],
+    overflow: Overflow,
+    splitter: WordSplitter,
+    metric: Metric,
+) -> Vec<(String, Range<usize>)> {
+    let pieces = pieces(line, columns, unbreakable, overflow, splitter, metric);
+    with_styles(line, &pieces)
+        .into_iter()
+        .zip(pieces.into_iter().map(|piece| piece.range))
+        .collect()
+}
+
+/// Splits the first piece off the line (see [`split()`]), and returns it with its byte range,
+/// and the rest of the line with the byte where it starts (after the styles carried over).
+pub(crate) fn split_first(
+    line: &str,
+    columns: usize,
+    unbreakable: &[Range<usize>],
+    overflow: Overflow,
+    splitter: WordSplitter,
+    metric: Metric,
+) -> (String, Range<usize>, String, usize) {
+    let pieces = pieces(line, columns, unbreakable, overflow, splitter, metric);
+    let first = with_styles(line, &pieces[..1]).remove(0);
+    let (rest, start) = match pieces.get(1) {
+        None => (String::new(), line.len()),
+        Some(piece) => {
+            // the styles open at the end of the first piece carry over
+            let mut styles = Styles::default();
+            styles.update(&line[..pieces[0].range.end]);
+            let start = piece.range.start;
+            (styles.opening() + &line[start..], start)
+        }
+    };
+    (first, pieces[0].range.clone(), rest, start)
+}
+
+/// A piece of a split line.
+pub(crate) struct Piece {
+    /// Its byte range in the line.
+    pub(crate) range: Range<usize>,
+    /// Whether it ends with a hyphen, see [`WordSplitter::Hyphenate`].
+    pub(crate) hyphen: bool,
+}
+
+/// The pieces of the line, see [`split()`].
+pub(crate) fn pieces(
+    line: &str,
+    columns: usize,
+    unbreakable: &[Range<usize>],
+    overflow: Overflow,
+    splitter: WordSplitter,
+    metric: Metric,
+) -> Vec<Piece> {
+    let unbreakable = &merge(unbreakable);
+    match overflow {
+        Overflow::WordWrap => word_ranges(line, columns, unbreakable, splitter, metric),
+        _ => piece_ranges(line, columns, unbreakable, metric)
+            .into_iter()
+            .map(|range| Piece {
+                range,
+                hyphen: false,
+            })
+            .collect(),
+    }
+}
+
+/// Merges the overlapping ranges, so that the line is never cut at the start of a range inside another one,
+/// e.g. a URL right after the [`Options::subsequent_prefix`], which would leave a piece with only the prefix.
+fn merge(ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut ranges = ranges.to_vec();
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start < last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// The pieces of the line, with the styles open at their start (re)opened, and those open at their end closed.
+fn with_styles(line: &str, pieces: &[Piece]) -> Vec<String> {
+    let mut styles = Styles::default();
+    let mut previous_end = 0;
+
+    pieces
+        .iter()
+        .map(|Piece { range, hyphen }| {
+            // the skipped spaces between words have no escape sequences
+            styles.update(&line[previous_end..range.start]);
+            let opening = styles.opening();
+            styles.update(&line[range.clone()]);
+            previous_end = range.end;
+            let hyphen = if *hyphen { "-" } else { "" };
+            format!(
+                "{opening}{}{hyphen}{}",
+                &line[range.clone()],
+                styles.closing()
+            )
+        })
+        .collect()
+}
+
+/// The byte ranges of the pieces of the line which fit in `columns`, split anywhere.
+fn piece_ranges(
+    line: &str,
+    columns: usize,
+    unbreakable: &[Range<usize>],
+    metric: Metric,
+) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+
+    while start < line.len() {
+        // the longest piece which fits, the escape sequences going with the character after them
+        let mut end = start;
+        let mut width = 0;
+        let mut index = start;
+        while index < line.len() {
+            if let Some(len) = ansi::escape_len(&line[index..]) {
+                index += len;
+                continue;
+            }
+            let len = cluster_len(&line[index..]);
+            let cluster_width = metric.cluster_width(&line[index..index + len]);
+            if width + cluster_width > columns && width > 0 {
+                break;
+            }
+            width += cluster_width;
+            index += len;
+            end = index;
+        }
+        if index == line.len() {
+            end = index;
+        }
+
+        if let Some(range) = unbreakable
+            .iter()
+            .find(|range| range.start < end && end < range.end)
+        {
+            end = if range.start > start {
+                range.start
+            } else {
+                range.end
+            };
+        }
+
+        ranges.push(start..end);
+        start = end;
+    }
+
+    if ranges.is_empty() {
+        ranges.push(0..0);
+    }
+
+    ranges
+}
+
+/// The pieces of the line which fit in `columns`, split between words.
+/// The spaces at the breaks are dropped, and the words wider than `columns` are split according to `splitter`.
+fn word_ranges(
+    line: &str,
+    columns: usize,
+    unbreakable: &[Range<usize>],
+    splitter: WordSplitter,
+    metric: Metric,
+) -> Vec<Piece> {
+    let piece = |range| Piece {
+        range,
+        hyphen: false,
+    };
+    let mut ranges: Vec<Piece> = vec![];
+    // the piece being filled, and its width
+    let mut current: Option<(Range<usize>, usize)> = None;
+    let mut previous_end = 0;
+
+    for word in words(line, unbreakable) {
+        let gap_width = metric.width(&line[previous_end..word.start]);
+        let word_width = metric.width(&line[word.clone()]);
+        previous_end = word.end;
+
+        if let Some((range, width)) = &mut current {
+            if *width + gap_width + word_width <= columns {
+                range.end = word.end;
+                *width += gap_width + word_width;
+                continue;
+            }
+        }
+        if let Some((range, _)) = current.take() {
+            ranges.push(piece(range));
+        }
+
+        // the first piece keeps the indentation if it fits
+        let start = if ranges.is_empty() && metric.width(&line[..word.end]) <= columns {
+            0
+        } else {
+            word.start
+        };
+        let width = metric.width(&line[start..word.end]);
+        if width <= columns || splitter == WordSplitter::Never {
+            current = Some((start..word.end, width));
+            continue;
+        }
+
+        // the word doesn't fit on a line of its own, the following words can go after its last piece
+        let hyphenate = splitter == WordSplitter::Hyphenate && columns > 1;
+        let shifted = unbreakable
+            .iter()
+            .filter(|range| range.start >= start && range.end <= word.end)
+            .map(|range| range.start - start..range.end - start)
+            .collect::<Vec<Range<usize>>>();
+        let limit = if hyphenate { columns - 1 } else { columns };
+        let mut pieces = piece_ranges(&line[start..word.end], limit, &shifted, metric)
+            .into_iter()
+            .map(|range| start + range.start..start + range.end)
+            .collect::<Vec<Range<usize>>>();
+        let last = pieces.pop().unwrap_or(start..word.end);
+        ranges.extend(pieces.into_iter().map(|range| Piece {
+            // no double hyphen when breaking after one
+            hyphen: hyphenate && !line[range.clone()].ends_with('-'),
+            range,
+        }));
+        current = Some((last.clone(), metric.width(&line[last])));
+    }
+
+    if let Some((range, _)) = current {
+        ranges.push(piece(range));
+    }
+    if ranges.is_empty() {
+        ranges.push(piece(0..0));
+    }
+
+    ranges
+}
+
+/// The byte ranges of the words of the line: the runs of characters separated by white-spaces,
+/// the white-spaces inside the `unbreakable` ranges not separating them.
+fn words(line: &str, unbreakable: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = None;
+
+    for (index, ch) in line.char_indices() {
+        let separates = ch.is_whitespace()
+            && !unbreakable
+                .iter()
+                .any(|range| range.start < index && index < range.end);
+        match (separates, start) {
+            (true, Some(word_start)) => {
+                words.push(word_start..index);
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(start) = start {
+        words.push(start..line.len());
+    }
+
+    words
+}
+
+/// Pads the text with spaces up to `columns`, placing it according to `align` (and `bias` when centering).
+/// Text which is already wider is returned as is.
+pub(crate) fn pad(text: &str, columns: usize, align: &Where, bias: Bias, metric: Metric) -> String {
+    let space = columns.saturating_sub(metric.width(text));
+
+    let before = match align {
+        Where::Left => 0,
+        Where::Center => (space + usize::from(bias)) / 2,
+        Where::Right => space,
+    };
+    let after = space - before;
+
+    format!("{}{text}{}", " ".repeat(before), " ".repeat(after))
+}
+
+/// Cuts the text to fit in `columns`, ending it with `ellipsis` when it's cut.
+/// Characters and emoji sequences aren't split, and the styles open at the cut are closed.
+pub(crate) fn truncate(text: &str, columns: usize, ellipsis: &str, metric: Metric) -> String {
+    if metric.width(text) <= columns {
+        return text.to_string();
+    }
+
+    let available = columns.saturating_sub(metric.width(ellipsis));
+    let mut end = 0;
+    let mut used = 0;
+    while end < text.len() {
+        let len = unit_len(&text[end..]);
+        let unit_width = metric.width(&text[end..end + len]);
+        if used + unit_width > available {
+            break;
+        }
+        used += unit_width;
+        end += len;
+    }
+
+    let mut styles = Styles::default();
+    styles.update(&text[..end]);
+    format!("{}{ellipsis}{}", &text[..end], styles.closing())
+}
+
+/// Splits a line into the text before the `start` column, between `start` and `end`, and after `end`.
+/// A character belongs to the part in which it starts, and an escape sequence to the part of the character after it.
+pub(crate) fn split_columns(
+    line: &str,
+    start: usize,
+    end: usize,
+    metric: Metric,
+) -> (String, String, String) {
+    let mut parts = (String::new(), String::new(), String::new());
+    let mut width = 0;
+    let mut index = 0;
+
+    while index < line.len() {
+        let part = if width < start {
+            &mut parts.0
+        } else if width < end {
+            &mut parts.1
+        } else {
+            &mut parts.2
+        };
+        match ansi::escape_len(&line[index..]) {
+            Some(len) => {
+                part.push_str(&line[index..index + len]);
+                index += len;
+            }
+            None => {
+                let len = cluster_len(&line[index..]);
+                part.push_str(&line[index..index + len]);
+                width += metric.cluster_width(&line[index..index + len]);
+                index += len;
+            }
+        }
+    }
+
+    parts
+}
@@ -0,0 +1,219 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::{layout, Error, Options};
+
+/// An [`io::Write`] adapter which aligns the lines written to it, and writes them to the inner writer,
+/// e.g. to align the output of existing code which writes to stdout or to a file.
+///
+/// The text is buffered until the end of each line, which is then aligned on its own,
+/// so [`Options::columns`] should be set (a line alone is as wide as itself).
+/// The last line, if it doesn't end with a newline, is aligned when the writer is dropped, or by [`AlignWriter::into_inner()`].
+///
+/// # Example
+/// ```
+/// use std::io::Write;
+/// use align_text::{AlignWriter, Options, Where};
+///
+/// let options = Options {
+///     align: Where::Right,
+///     columns: Some(10),
+///     ..Default::default()
+/// };
+/// let mut writer = AlignWriter::new(Vec::new(), options);
+/// write!(writer, "total: ").unwrap();
+/// writeln!(writer, "{}", 42).unwrap();
+/// write!(writer, "done").unwrap();
+///
+/// let output = writer.into_inner().unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), " total: 42\n      done\n");
+/// ```
+pub struct AlignWriter<W: Write> {
+    /// Only `None` once it's been taken by [`AlignWriter::into_inner()`].
+    inner: Option<W>,
+    options: Options,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> AlignWriter<W> {
+    /// Aligns the lines written to it with the options, and writes them to `inner`.
+    pub fn new(inner: W, options: Options) -> AlignWriter<W> {
+        AlignWriter {
+            inner: Some(inner),
+            options,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner
+            .as_ref()
+            .expect("the inner writer is only taken once")
+    }
+
+    /// Aligns and writes the last line if it wasn't ended, and returns the inner writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.finish()?;
+        Ok(self
+            .inner
+            .take()
+            .expect("the inner writer is only taken once"))
+    }
+
+    /// Aligns and writes the rest of the text.
+    fn finish(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let line = std::mem::take(&mut self.buffer);
+        self.write_line(&line)?;
+        self.flush()
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        let line =
+            std::str::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let aligned =
+            align_line(line, &self.options).map_err(|e| io::Error::other(e.to_string()))?;
+        let inner = self
+            .inner
+            .as_mut()
+            .expect("the inner writer is only taken once");
+        for line in aligned {
+            writeln!(inner, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for AlignWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        while let Some(end) = self.buffer.iter().position(|&byte| byte == b'\n') {
+            let line = self.buffer.drain(..=end).collect::<Vec<u8>>();
+            self.write_line(&line[..end])?;
+        }
+        Ok(buf.len())
+    }
+
+    /// Flushes the inner writer. The text of an unfinished line stays buffered.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner
+            .as_mut()
+            .expect("the inner writer is only taken once")
+            .flush()
+    }
+}
+
+impl<W: Write> Drop for AlignWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            // there's no way to report the error
+            let _ = self.finish();
+        }
+    }
+}
+
+/// A [`fmt::Write`] adapter which aligns the lines written to it, like [`AlignWriter`],
+/// e.g. to build a large report in a [`String`] with `write!`.
+///
+/// As [`fmt::Error`] can't tell why, a line which can't be aligned (see [`Error`]) fails with it.
+///
+/// # Example
+/// ```
+/// use std::fmt::Write;
+/// use align_text::{AlignFmtWriter, Options, Where};
+///
+/// let options = Options {
+///     align: Where::Center,
+///     columns: Some(11),
+///     ..Default::default()
+/// };
+/// let mut writer = AlignFmtWriter::new(String::new(), options);
+/// writeln!(writer, "Report").unwrap();
+/// write!(writer, "{} items", 3).unwrap();
+///
+/// assert_eq!(writer.into_inner().unwrap(), "  Report\n  3 items\n");
+/// ```
+pub struct AlignFmtWriter<W: fmt::Write> {
+    /// Only `None` once it's been taken by [`AlignFmtWriter::into_inner()`].
+    inner: Option<W>,
+    options: Options,
+    buffer: String,
+}
+
+impl<W: fmt::Write> AlignFmtWriter<W> {
+    /// Aligns the lines written to it with the options, and writes them to `inner`.
+    pub fn new(inner: W, options: Options) -> AlignFmtWriter<W> {
+        AlignFmtWriter {
+            inner: Some(inner),
+            options,
+            buffer: String::new(),
+        }
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner
+            .as_ref()
+            .expect("the inner writer is only taken once")
+    }
+
+    /// Aligns and writes the last line if it wasn't ended, and returns the inner writer.
+    pub fn into_inner(mut self) -> Result<W, fmt::Error> {
+        self.finish()?;
+        Ok(self
+            .inner
+            .take()
+            .expect("the inner writer is only taken once"))
+    }
+
+    /// Aligns and writes the rest of the text.
+    fn finish(&mut self) -> fmt::Result {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let line = std::mem::take(&mut self.buffer);
+        self.write_line(&line)
+    }
+
+    fn write_line(&mut self, line: &str) -> fmt::Result {
+        let aligned = align_line(line, &self.options).map_err(|_| fmt::Error)?;
+        let inner = self
+            .inner
+            .as_mut()
+            .expect("the inner writer is only taken once");
+        for line in aligned {
+            writeln!(inner, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for AlignFmtWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.buffer.push_str(s);
+        while let Some(end) = self.buffer.find('\n') {
+            let line = self.buffer.drain(..=end).collect::<String>();
+            self.write_line(&line[..end])?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> Drop for AlignFmtWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            // there's no way to report the error
+            let _ = self.finish();
+        }
+    }
+}
+
+/// Aligns a line on its own, which can give several lines when it's wrapped.
+/// The `\r` of a `\r\n` line ending is left out.
+fn align_line(line: &str, options: &Options) -> Result<Vec<String>, Error> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    Ok(layout(&[line.to_string()], options)?.into_rendered(options.keep_spaces))
+}
@@ -0,0 +1,29 @@
+use arboard::Clipboard;
+
+/// The options reading and writing the system clipboard.
+#[derive(clap::Args, Debug, Clone)]
+pub struct ClipboardArgs {
+    /// Read the text from the system clipboard instead of stdin.
+    #[arg(long, action, conflicts_with_all = ["watch", "serve", "progress", "files", "stream"])]
+    pub paste: bool,
+
+    /// Write the aligned text to the system clipboard instead of stdout.
+    #[arg(long, action, conflicts_with_all = ["watch", "serve", "interactive", "check", "progress", "stream"])]
+    pub copy: bool,
+}
+
+/// Reads the text in the system clipboard, as lines.
+pub fn paste() -> Result<Vec<String>, String> {
+    let text = Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| format!("couldn't read the clipboard: {e}"))?;
+
+    Ok(text.lines().map(|line| line.to_string()).collect())
+}
+
+/// Replaces the content of the system clipboard with the text.
+pub fn copy(text: &str) -> Result<(), String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("couldn't write to the clipboard: {e}"))
+}
@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+/// Resets all the colors and styles.
+pub const RESET: &str = "\x1b[0m";
+
+const NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// The colors of the padding, as an ANSI escape sequence (SGR),
+/// parsed from space or comma-separated `fg:COLOR` and `bg:COLOR` (or just `COLOR` for the background).
+/// A color is a name (`blue`, `bright-blue`, ...), a number of the 256-color palette, or `#rrggbb`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PadColor(String);
+
+impl PadColor {
+    /// The escape sequence which sets the colors.
+    pub fn sgr(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for PadColor {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let codes = input
+            .split([',', ' '])
+            .filter(|part| !part.is_empty())
+            .map(|part| match part.split_once(':') {
+                Some(("fg", color)) => color_code(color, 30),
+                Some(("bg", color)) => color_code(color, 40),
+                Some(_) => Err(format!(
+                    "invalid color '{part}', expected fg:COLOR or bg:COLOR"
+                )),
+                None => color_code(part, 40),
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+
+        if codes.is_empty() {
+            return Err("no color given".to_string());
+        }
+        Ok(PadColor(format!("\x1b[{}m", codes.join(";"))))
+    }
+}
+
+/// The SGR parameters of a color, `base` being 30 for the foreground and 40 for the background.
+fn color_code(color: &str, base: u8) -> Result<String, String> {
+    let invalid = || format!("invalid color '{color}'");
+
+    if let Some(index) = NAMES.iter().position(|name| *name == color) {
+        return Ok((base as usize + index).to_string());
+    }
+    if let Some(name) = color.strip_prefix("bright-") {
+        let index = NAMES
+            .iter()
+            .position(|known| *known == name)
+            .ok_or_else(invalid)?;
+        return Ok((base as usize + 60 + index).to_string());
+    }
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let channel = |range| u8::from_str_radix(&hex[range], 16).map_err(|_| invalid());
+        let (red, green, blue) = (channel(0..2)?, channel(2..4)?, channel(4..6)?);
+        return Ok(format!("{};2;{red};{green};{blue}", base + 8));
+    }
+    let index = color.parse::<u8>().map_err(|_| invalid())?;
+    Ok(format!("{};5;{index}", base + 8))
+}
@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use align_text::{Bias, Where};
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, ValueEnum};
+use serde::Deserialize;
+
+use crate::serve::split_words;
+use crate::{Args, WrapMode};
+
+/// Default values for the options, which apply when they aren't given on the command line.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Defaults {
+    align: Option<String>,
+    outer: Option<String>,
+    inner: Option<String>,
+    columns: Option<usize>,
+    wrap: Option<Wrap>,
+    trim: Option<bool>,
+    keep: Option<bool>,
+    bias: Option<String>,
+    tab_width: Option<NonZeroUsize>,
+    fill: Option<char>,
+    /// Presets for the files with a given extension, see [`Defaults::for_path()`].
+    #[serde(default)]
+    filetype: HashMap<String, Defaults>,
+}
+
+/// The default of `--wrap`: whether to wrap (anywhere), or the mode to wrap with, e.g. `wrap = "overflow-only"`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+enum Wrap {
+    Flag(bool),
+    Mode(String),
+}
+
+impl Wrap {
+    /// The mode of `--wrap`, `None` if it's turned off.
+    fn mode(&self) -> Result<Option<WrapMode>, String> {
+        match self {
+            Wrap::Flag(wrap) => Ok(wrap.then_some(WrapMode::Anywhere)),
+            Wrap::Mode(mode) => WrapMode::from_str(mode, true)
+                .map(Some)
+                .map_err(|_| format!("invalid wrap mode '{mode}'")),
+        }
+    }
+}
+
+impl Defaults {
+    /// Reads the defaults from `~/.config/align/config.toml`, then `./.align.toml` whose values take precedence.
+    /// Missing files are skipped.
+    pub fn from_config_files() -> Result<Defaults, String> {
+        let mut defaults = Defaults::default();
+
+        for path in config_paths() {
+            if path.is_file() {
+                defaults = Defaults::from_file(&path)?.or(defaults);
+            }
+        }
+
+        Ok(defaults)
+    }
+
+    /// Reads the defaults from the environment variables:
+    /// * `ALIGN_OPTS`: options as they'd be written on the command line, e.g. `--trim -b right`,
+    ///   split into words like a shell would (see [`split_words()`]).
+    /// * `ALIGN_WHERE`: same as `--align`.
+    /// * `ALIGN_COLUMNS`: same as `--columns`.
+    ///
+    /// The specific variables take precedence over `ALIGN_OPTS`.
+    pub fn from_env() -> Result<Defaults, String> {
+        let mut defaults = Defaults::default();
+
+        if let Some(opts) = env_var("ALIGN_OPTS")? {
+            let words = split_words(&opts).map_err(|e| format!("invalid ALIGN_OPTS, {e}"))?;
+            let matches = Args::command()
+                .no_binary_name(true)
+                .try_get_matches_from(words)
+                .map_err(|e| {
+                    let message = e.to_string();
+                    let message = message.lines().next().unwrap_or_default();
+                    format!(
+                        "invalid ALIGN_OPTS, {}",
+                        message.trim_start_matches("error: ")
+                    )
+                })?;
+            defaults = Defaults::from_matches(&matches)?;
+        }
+
+        let columns = env_var("ALIGN_COLUMNS")?
+            .map(|columns| {
+                columns
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid ALIGN_COLUMNS '{columns}'"))
+            })
+            .transpose()?;
+
+        let specific = Defaults {
+            align: env_var("ALIGN_WHERE")?,
+            columns,
+            ..Default::default()
+        };
+
+        Ok(specific.or(defaults))
+    }
+
+    /// Takes the values given on the command line, only supports the options which have defaults.
+    fn from_matches(matches: &ArgMatches) -> Result<Defaults, String> {
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+        let value = |id: &str| {
+            matches
+                .get_raw(id)
+                .and_then(|mut values| values.next())
+                .map(|value| value.to_string_lossy().to_string())
+        };
+        let flag = |id: &str| given(id).then(|| matches.get_flag(id));
+
+        let supported = [
+            "align",
+            "outer",
+            "inner",
+            "columns",
+            "wrap",
+            "trim",
+            "keep",
+            "bias",
+            "tab_width",
+            "fill",
+        ];
+        if let Some(arg) = Args::command()
+            .get_arguments()
+            .find(|arg| given(arg.get_id().as_str()) && !supported.contains(&arg.get_id().as_str()))
+        {
+            return Err(format!("--{} isn't supported in ALIGN_OPTS", arg.get_id()));
+        }
+
+        Ok(Defaults {
+            align: value("align"),
+            outer: given("outer").then(|| value("outer")).flatten(),
+            inner: given("inner").then(|| value("inner")).flatten(),
+            columns: matches.get_one::<usize>("columns").copied(),
+            wrap: given("wrap")
+                .then(|| value("wrap").map(Wrap::Mode))
+                .flatten(),
+            trim: flag("trim"),
+            keep: flag("keep"),
+            bias: given("bias").then(|| value("bias")).flatten(),
+            tab_width: matches.get_one::<NonZeroUsize>("tab_width").copied(),
+            fill: matches.get_one::<char>("fill").copied(),
+            filetype: HashMap::new(),
+        })
+    }
+
+    /// Reads the defaults from a modeline, like vim's: a comment starting with `align:` followed by the options,
+    /// e.g. `# align: center columns=60 wrap` or `<!-- align: right trim -->`. `None` if the line isn't one.
+    ///
+    /// The options are `left`, `center`, or `right` (for `align`), `wrap`, `trim`, and `keep` (or `nowrap`, ...),
+    /// or `NAME=VALUE` with the names of the config, but `fill` (the output of all the files is padded alike).
+    pub fn from_modeline(line: &str) -> Option<Result<Defaults, String>> {
+        let (leader, options) = line.trim().split_once("align:")?;
+        // only after a comment's marker, so that e.g. a YAML key isn't taken for one
+        let leader = leader.trim_end();
+        if leader.is_empty() || !leader.chars().all(|ch| ch.is_ascii_punctuation()) {
+            return None;
+        }
+        let options = options.trim_end();
+        let options = options
+            .strip_suffix("-->")
+            .or_else(|| options.strip_suffix("*/"))
+            .unwrap_or(options);
+
+        let mut defaults = Defaults::default();
+        for option in options.split_whitespace() {
+            if let Err(e) = defaults.set(option) {
+                return Some(Err(format!("{e} in modeline")));
+            }
+        }
+        Some(Ok(defaults))
+    }
+
+    /// Sets one of the options of a modeline.
+    fn set(&mut self, option: &str) -> Result<(), String> {
+        let where_value = |value: &str| {
+            value
+                .to_lowercase()
+                .parse::<Where>()
+                .map(|_| value.to_string())
+                .map_err(|e| format!("{e} '{value}'"))
+        };
+        let flag = |value: &str| {
+            value
+                .parse::<bool>()
+                .map_err(|_| format!("invalid value '{value}' for '{option}'"))
+        };
+
+        match option.split_once('=') {
+            Some(("align", value)) => self.align = Some(where_value(value)?),
+            Some(("outer", value)) => self.outer = Some(where_value(value)?),
+            Some(("inner", value)) => self.inner = Some(where_value(value)?),
+            Some(("columns", value)) => {
+                self.columns = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid number of columns '{value}'"))?,
+                )
+            }
+            Some(("bias", value)) => {
+                value
+                    .to_lowercase()
+                    .parse::<Bias>()
+                    .map_err(|e| format!("{e} '{value}'"))?;
+                self.bias = Some(value.to_string());
+            }
+            Some(("tab_width", value)) => {
+                self.tab_width = Some(
+                    value
+                        .parse::<NonZeroUsize>()
+                        .map_err(|_| format!("invalid tab width '{value}'"))?,
+                )
+            }
+            Some(("wrap", value)) => {
+                let wrap = flag(value).map_or_else(|_| Wrap::Mode(value.to_string()), Wrap::Flag);
+                wrap.mode()?;
+                self.wrap = Some(wrap);
+            }
+            Some(("trim", value)) => self.trim = Some(flag(value)?),
+            Some(("keep", value)) => self.keep = Some(flag(value)?),
+            Some((name, _)) => return Err(format!("unknown option '{name}'")),
+            None => match option {
+                "wrap" | "nowrap" => self.wrap = Some(Wrap::Flag(option == "wrap")),
+                "trim" | "notrim" => self.trim = Some(option == "trim"),
+                "keep" | "nokeep" => self.keep = Some(option == "keep"),
+                _ => {
+                    self.align = Some(
+                        where_value(option).map_err(|_| format!("unknown option '{option}'"))?,
+                    )
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Finds the modeline of the text, on its first or last line, see [`Defaults::from_modeline()`].
+    /// Returns its index, and the defaults it sets.
+    pub fn find_modeline(lines: &[String]) -> Result<Option<(usize, Defaults)>, String> {
+        let last = lines.len().saturating_sub(1);
+        let candidates = if last == 0 { vec![0] } else { vec![0, last] };
+
+        for index in candidates {
+            let Some(line) = lines.get(index) else {
+                break;
+            };
+            if let Some(defaults) = Defaults::from_modeline(line) {
+                return defaults
+                    .map(|defaults| Some((index, defaults)))
+                    .map_err(|e| format!("{e}, line {}", index + 1));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn from_file(path: &Path) -> Result<Defaults, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {e}", path.display()))?;
+
+        toml::from_str(&content).map_err(|e| format!("invalid config {}: {e}", path.display()))
+    }
+
+    /// Takes the values of `self`, and the missing ones from `other`.
+    pub fn or(self, other: Defaults) -> Defaults {
+        let mut filetype = other.filetype;
+        for (extension, preset) in self.filetype {
+            let other_preset = filetype.remove(&extension).unwrap_or_default();
+            filetype.insert(extension, preset.or(other_preset));
+        }
+
+        Defaults {
+            align: self.align.or(other.align),
+            outer: self.outer.or(other.outer),
+            inner: self.inner.or(other.inner),
+            columns: self.columns.or(other.columns),
+            wrap: self.wrap.or(other.wrap),
+            trim: self.trim.or(other.trim),
+            keep: self.keep.or(other.keep),
+            bias: self.bias.or(other.bias),
+            tab_width: self.tab_width.or(other.tab_width),
+            fill: self.fill.or(other.fill),
+            filetype,
+        }
+    }
+
+    /// Swaps left and right in the alignments and the bias, for --mirror.
+    pub fn mirrored(self) -> Defaults {
+        let swap = |value: Option<String>| {
+            value.map(|value| match value.to_lowercase().as_str() {
+                "l" | "left" => "right".to_string(),
+                "r" | "right" => "left".to_string(),
+                _ => value,
+            })
+        };
+
+        Defaults {
+            align: swap(self.align),
+            outer: swap(self.outer),
+            inner: swap(self.inner),
+            bias: swap(self.bias),
+            ..self
+        }
+    }
+
+    /// The defaults for the file at `path`: the preset for its extension (in `[filetype.<extension>]`),
+    /// and the general defaults for the values it doesn't set.
+    pub fn for_path(mut self, path: &Path) -> Defaults {
+        let preset = path
+            .extension()
+            .and_then(|extension| self.filetype.remove(extension.to_string_lossy().as_ref()));
+
+        match preset {
+            Some(preset) => preset.or(self),
+            None => self,
+        }
+    }
+
+    /// Sets the args which weren't given on the command line to the default values.
+    /// The subcommands only have some of the options, the others are never given.
+    /// Returns the ids of the options it set (the flags only if it turned them on), e.g. to check them like those given.
+    pub fn apply(
+        &self,
+        args: &mut Args,
+        matches: &ArgMatches,
+    ) -> Result<Vec<&'static str>, String> {
+        let source = |id: &str| match matches.try_contains_id(id) {
+            Ok(_) => matches.value_source(id),
+            Err(_) => None,
+        };
+        let given = |id: &str| source(id) == Some(ValueSource::CommandLine);
+        let where_value = |value: &str| {
+            value
+                .to_lowercase()
+                .parse::<Where>()
+                .map_err(|e| format!("{e} '{value}' in defaults"))
+        };
+
+        let mut applied = vec![];
+
+        // the shorthand is a default for both, the specific values take precedence
+        if let (Some(outer), false) = (
+            self.outer.as_ref().or(self.align.as_ref()),
+            given("align") || given("outer"),
+        ) {
+            args.outer = where_value(outer)?;
+            applied.push("outer");
+        }
+        if let (Some(inner), false) = (
+            self.inner.as_ref().or(self.align.as_ref()),
+            given("align") || given("inner"),
+        ) {
+            args.inner = where_value(inner)?;
+            applied.push("inner");
+        }
+
+        if let (Some(columns), false) = (self.columns, given("columns")) {
+            args.columns = Some(columns);
+            applied.push("columns");
+        }
+        // `align wrap` always wraps, its mode has a default value
+        if let (Some(wrap), None) = (&self.wrap, source("wrap")) {
+            args.wrap = wrap.mode().map_err(|e| format!("{e} in defaults"))?;
+            if args.wrap.is_some() {
+                applied.push("wrap");
+            }
+        }
+        if let (Some(trim), false) = (self.trim, given("trim")) {
+            args.trim = trim;
+            if trim {
+                applied.push("trim");
+            }
+        }
+        if let (Some(keep), false) = (self.keep, given("keep")) {
+            args.keep = keep;
+            if keep {
+                applied.push("keep");
+            }
+        }
+        if let (Some(bias), false) = (&self.bias, given("bias")) {
+            args.bias = bias
+                .to_lowercase()
+                .parse::<Bias>()
+                .map_err(|e| format!("{e} '{bias}' in defaults"))?;
+            applied.push("bias");
+        }
+        if let (Some(tab_width), false) = (self.tab_width, given("tab_width")) {
+            args.tab_width = Some(tab_width);
+            applied.push("tab_width");
+        }
+        // --show-spaces shows the padding its own way
+        if let (Some(fill), false) = (self.fill, given("fill") || given("show_spaces")) {
+            args.fill = Some(fill);
+            applied.push("fill");
+        }
+
+        Ok(applied)
+    }
+}
+
+/// The value of an environment variable, `None` if it's not set or empty.
+fn env_var(name: &str) -> Result<Option<String>, String> {
+    match std::env::var(name) {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(format!("invalid {name}: {e}")),
+    }
+}
+
+/// The config files, from lowest to highest precedence.
+fn config_paths() -> Vec<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    config_dir
+        .map(|dir| dir.join("align").join("config.toml"))
+        .into_iter()
+        .chain([PathBuf::from(".align.toml")])
+        .collect()
+}
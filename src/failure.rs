@@ -0,0 +1,35 @@
+use std::fmt::Display;
+use std::process::ExitCode;
+
+/// Why a run failed, which determines the exit code.
+#[derive(Debug)]
+pub enum Failure {
+    /// The text can't be aligned with the options, e.g. there aren't enough columns: exits with 1.
+    Align(String),
+    /// The text isn't aligned with --check, with the number of lines which aren't: exits with 1.
+    Check(usize),
+    /// Invalid options, config, or environment: exits with 2, like the errors of the argument parser.
+    Usage(String),
+    /// Reading the input or writing the output failed: exits with 3.
+    Io(String),
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Failure::Align(_) | Failure::Check(_) => ExitCode::from(1),
+            Failure::Usage(_) => ExitCode::from(2),
+            Failure::Io(_) => ExitCode::from(3),
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Align(e) | Failure::Usage(e) | Failure::Io(e) => write!(f, "{e}"),
+            Failure::Check(1) => write!(f, "1 line isn't aligned"),
+            Failure::Check(lines) => write!(f, "{lines} lines aren't aligned"),
+        }
+    }
+}
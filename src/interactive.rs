@@ -0,0 +1,166 @@
+use std::io::{stderr, Write};
+
+use align_text::{Bias, Where};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::{cursor, execute, queue, style, terminal};
+
+use crate::{align_input, get_cols_wrap, get_terimnal_width, get_terminal_height, Args, WrapMode};
+
+const HELP: &str = "o/i/a: outer/inner/both  ←/→: columns  0: terminal's  w: wrap  t: trim  b: bias  enter: print  q: quit";
+
+/// Shows the aligned lines, letting the options be changed with keypresses.
+/// On enter, the aligned lines are printed, and the equivalent options are echoed to stderr.
+pub fn interactive(lines: &[String], args: &mut Args) -> Result<Option<Vec<String>>, String> {
+    let mut err = stderr();
+    terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+    execute!(err, terminal::EnterAlternateScreen, cursor::Hide).map_err(|e| e.to_string())?;
+
+    let result = run(lines, args, &mut err);
+
+    execute!(err, cursor::Show, terminal::LeaveAlternateScreen).map_err(|e| e.to_string())?;
+    terminal::disable_raw_mode().map_err(|e| e.to_string())?;
+
+    let result = result?;
+    if result.is_some() {
+        eprintln!("align {}", flags(args));
+    }
+    Ok(result)
+}
+
+/// The event loop, returns the aligned lines if they should be printed.
+fn run(
+    lines: &[String],
+    args: &mut Args,
+    err: &mut impl Write,
+) -> Result<Option<Vec<String>>, String> {
+    loop {
+        let aligned = align(lines, args);
+        draw(err, &aligned, args).map_err(|e| e.to_string())?;
+
+        let key = match event::read().map_err(|e| e.to_string())? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Char('o') => args.outer = next(&args.outer),
+            KeyCode::Char('i') => args.inner = next(&args.inner),
+            KeyCode::Char('a') => {
+                args.outer = next(&args.outer);
+                args.inner = args.outer.clone();
+            }
+            KeyCode::Right | KeyCode::Char('+') => {
+                args.columns = Some(columns(args)? + 1);
+            }
+            KeyCode::Left | KeyCode::Char('-') => {
+                args.columns = Some(columns(args)?.saturating_sub(1).max(1));
+            }
+            KeyCode::Char('0') => args.columns = None,
+            KeyCode::Char('w') => {
+                args.wrap = match args.wrap {
+                    Some(_) => None,
+                    None => Some(WrapMode::Anywhere),
+                }
+            }
+            KeyCode::Char('t') => args.trim = !args.trim,
+            KeyCode::Char('b') => {
+                args.bias = match args.bias {
+                    Bias::Left => Bias::Right,
+                    Bias::Right => Bias::Alternate,
+                    Bias::Alternate => Bias::Left,
+                }
+            }
+            KeyCode::Enter => return aligned.map(Some),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// The aligned lines with the current options.
+fn align(lines: &[String], args: &Args) -> Result<Vec<String>, String> {
+    let terminal_width = match args.columns {
+        None => Some(get_terimnal_width()?),
+        Some(_) => None,
+    };
+    let layout = align_input(lines.to_vec(), args, get_cols_wrap(args, terminal_width))?;
+    Ok(layout.into_rendered(args.keep))
+}
+
+/// Draws the aligned lines (or the error), and the current options and keys at the bottom.
+fn draw(
+    out: &mut impl Write,
+    aligned: &Result<Vec<String>, String>,
+    args: &Args,
+) -> std::io::Result<()> {
+    let rows = get_terminal_height().unwrap_or(24);
+    let body = match aligned {
+        Ok(lines) => lines.clone(),
+        Err(e) => vec![format!("error: {e}")],
+    };
+
+    queue!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+    for line in body.iter().take(rows.saturating_sub(2)) {
+        queue!(out, style::Print(line), style::Print("\r\n"))?;
+    }
+
+    let bottom = u16::try_from(rows.saturating_sub(2)).unwrap_or(u16::MAX);
+    queue!(
+        out,
+        cursor::MoveTo(0, bottom),
+        style::Print(format!("align {}\r\n", flags(args))),
+        style::Print(HELP)
+    )?;
+    out.flush()
+}
+
+/// The current number of columns, the terminal's width if not set.
+fn columns(args: &Args) -> Result<usize, String> {
+    args.columns.map_or_else(get_terimnal_width, Ok)
+}
+
+fn next(wh: &Where) -> Where {
+    match wh {
+        Where::Left => Where::Center,
+        Where::Center => Where::Right,
+        Where::Right => Where::Left,
+    }
+}
+
+fn name(wh: &Where) -> &'static str {
+    match wh {
+        Where::Left => "left",
+        Where::Center => "center",
+        Where::Right => "right",
+    }
+}
+
+/// The command line options equivalent to the ones changed interactively.
+fn flags(args: &Args) -> String {
+    let mut flags = if args.outer == args.inner {
+        format!("-a {}", name(&args.outer))
+    } else {
+        format!("-o {} -i {}", name(&args.outer), name(&args.inner))
+    };
+
+    if let Some(columns) = args.columns {
+        flags += &format!(" -c {columns}");
+    }
+    match args.wrap {
+        Some(WrapMode::Anywhere) => flags += " -w",
+        Some(WrapMode::OverflowOnly) => flags += " --wrap=overflow-only",
+        None => {}
+    }
+    if args.trim {
+        flags += " -t";
+    }
+    if args.bias == Bias::Right {
+        flags += " -b right";
+    }
+
+    flags
+}
@@ -0,0 +1,2460 @@
+//! The command-line interface of `align`, built on [`align_text`]: its options ([`Args`]),
+//! the subcommands ([`Command`]), and the alignment of the lines according to them ([`align_lines()`]),
+//! so that other programs get the same results as the command line.
+
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod color;
+mod config;
+mod failure;
+mod interactive;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod output;
+mod pager;
+mod paginate;
+mod progress;
+mod ranges;
+mod serve;
+mod stats;
+mod stream;
+pub mod subcommand;
+mod watch;
+
+use std::fmt::Display;
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use align_text::*;
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+#[cfg(feature = "clipboard")]
+use clipboard::ClipboardArgs;
+use color::PadColor;
+use config::Defaults;
+pub use failure::Failure;
+use flate2::read::MultiGzDecoder;
+use output::{OutputFormat, Style};
+use pager::Paging;
+use paginate::PageArgs;
+use progress::Progress;
+use ranges::RegionArgs;
+use regex::Regex;
+use serve::ServeArgs;
+use stats::Stats;
+use stream::StreamArgs;
+use subcommand::{FlowArgs, TableArgs};
+
+/// The command line: the options of `align`, or a subcommand with its own.
+#[derive(Parser, Debug)]
+#[command(author, version, long_about = None)]
+#[command(about = "Aligns a block of text within the terminal (or a specified number of columns).")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(flatten)]
+    args: Args,
+
+    /// Print the man page and exit.
+    #[arg(long, hide = true, action)]
+    generate_man: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// The options of `align` (and `align block`): those shared by the modes, and the groups of options of each mode
+/// (e.g. `--stream` and `--jobs`), which are defined with the mode and flattened here.
+#[derive(Parser, Debug, Clone)]
+pub struct Args {
+    /// Where to align the block of text.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        default_value_t,
+        ignore_case = true,
+        conflicts_with = "align"
+    )]
+    outer: Where,
+
+    /// Where to align text inside the block.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        default_value_t,
+        ignore_case = true,
+        conflicts_with = "align"
+    )]
+    inner: Where,
+
+    /// Shorthand for specifiying both.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        ignore_case = true,
+        conflicts_with = "outer",
+        conflicts_with = "inner"
+    )]
+    align: Option<Where>,
+
+    /// Number of columns. Takes text's width if 0, terminal's width if unspecified.
+    #[arg(short, long)]
+    columns: Option<usize>,
+
+    /// Take the width which PERCENT of the lines fit in (e.g. 95) as the number of columns, instead of the terminal's
+    /// (if it's narrower) or the text's width, so that a few very long lines don't widen the whole block. They're wrapped.
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+        conflicts_with = "columns",
+        requires = "wrap"
+    )]
+    width_percentile: Option<u8>,
+
+    /// Use the text's width as the number of columns, unless it's wider than N (or the terminal's width, if it's narrower):
+    /// then only the lines which are wider are wrapped, and the others are left as they are.
+    #[arg(long, value_name = "N", conflicts_with = "columns")]
+    max_columns: Option<NonZeroUsize>,
+
+    /// Round the text's width up to a multiple of N when it's the number of columns
+    /// (and the terminal's width down, to still fit in it), e.g. to line up with tab stops or grid cells.
+    #[arg(long, value_name = "N")]
+    snap: Option<NonZeroUsize>,
+
+    /// Wrap the lines of text to fit in the number of columns: anywhere, or with 'overflow-only',
+    /// between words, only breaking the words which are too long.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "anywhere"
+    )]
+    wrap: Option<WrapMode>,
+
+    /// How to split the words which are wider than the columns with --wrap=overflow-only.
+    #[arg(value_enum, long, default_value_t, value_name = "SPLITTER")]
+    word_splitter: Splitter,
+
+    /// What to do with the lone carriage returns, U+2028, and U+2029 in the lines, which would throw off their widths.
+    #[arg(value_enum, long, default_value_t, value_name = "MODE")]
+    line_separators: Separators,
+
+    /// Trim the spaces around the lines before aligning.
+    #[arg(short, long, action)]
+    trim: bool,
+
+    /// Remove the white-spaces all the lines start with before aligning, keeping their relative indentation.
+    #[arg(long, action, conflicts_with = "trim")]
+    dedent: bool,
+
+    /// Only align the text of the comments starting with MARKER (e.g. '//' or '#'), leaving the code as it is.
+    #[arg(long, value_name = "MARKER")]
+    comment: Option<String>,
+
+    /// Leave the longest start the lines have in common (e.g. 'INFO app[123]: ') as it is, and only align the rest.
+    #[arg(long, action, conflicts_with_all = ["comment", "quotes"])]
+    common_prefix: bool,
+
+    /// Join the lines of each paragraph (separated by blank lines) before aligning, to wrap them again,
+    /// like vim's 'gq'. With --comment or --quotes, the paragraphs are made of the lines with the same markers.
+    #[arg(long, action, conflicts_with_all = ["pipeline", "flow", "skip_errors"])]
+    reflow: bool,
+
+    /// Align the text of the quoted lines (e-mail quotes and markdown blockquotes, starting with '>', '>>', ...)
+    /// without their quote markers, which are put back on each line, wrapped ones included.
+    #[arg(long, action, conflicts_with = "comment")]
+    quotes: bool,
+
+    /// Treat the text as markdown: don't break code spans, links, and emphasis when wrapping,
+    /// and leave the fenced code blocks as they are.
+    #[arg(long, action)]
+    markdown: bool,
+
+    /// Don't break the URLs and file paths when wrapping.
+    #[arg(long, action)]
+    keep_urls: bool,
+
+    /// Don't break the tokens matching this regular expression when wrapping (can be repeated).
+    #[arg(long, value_name = "REGEX")]
+    unbreakable: Vec<String>,
+
+    /// Start the remainders of wrapped lines with PREFIX (e.g. '│ ' or '> '), counted in the number of columns.
+    #[arg(long, value_name = "PREFIX")]
+    continuation: Option<String>,
+
+    /// End the wrapped lines which continue on the next line with MARKER (e.g. '↩' or '\\'), counted in the number of columns,
+    /// to tell the wrapping from the text's own line breaks.
+    #[arg(long, value_name = "MARKER")]
+    wrap_marker: Option<String>,
+
+    /// Where to align the remainders of wrapped lines, like the other lines if unspecified.
+    #[arg(value_enum, long, ignore_case = true, value_name = "WHERE")]
+    remainder: Option<Where>,
+
+    /// Where to align the last line of each paragraph, like the other lines if unspecified.
+    #[arg(value_enum, long, ignore_case = true, value_name = "WHERE")]
+    last_line: Option<Where>,
+
+    /// Align each line according to its prefix ('<' left, '^' center, '>' right), which is removed.
+    #[arg(long, action)]
+    prefixed: bool,
+
+    /// Replace the control characters (tabs included) with visible placeholders before aligning:
+    /// in caret notation ('^M'), or as control 'pictures' ('␍'). The no-break spaces are shown as '·'.
+    #[arg(
+        value_enum,
+        long,
+        value_name = "STYLE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "caret"
+    )]
+    reveal: Option<Reveal>,
+
+    /// Normalize the text to a Unicode normalization form before aligning: composed ('nfc'), or composed with the
+    /// compatibility characters replaced ('nfkc'), so that e.g. an 'é' typed as 'e' and a combining accent aligns like 'é'.
+    #[arg(
+        value_enum,
+        long,
+        value_name = "FORM",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "nfc"
+    )]
+    normalize: Option<Normalization>,
+
+    /// Escape the characters which aren't printable ASCII like Rust does ('\t', '\u{e9}') before aligning,
+    /// so that the output is pure ASCII of exactly the computed width.
+    #[arg(long, action, conflicts_with_all = ["reveal", "pad_color"])]
+    escape: bool,
+
+    /// Expand the tabs into spaces, up to the next multiple of N columns, before aligning
+    /// (they're counted as 1 column otherwise).
+    #[arg(long, value_name = "N")]
+    tab_width: Option<NonZeroUsize>,
+
+    /// Fail if the text contains hard tabs, reporting where they are.
+    #[arg(long, action)]
+    forbid_tabs: bool,
+
+    /// Leave the lines which can't fit in the columns as they are, and align the others, instead of failing.
+    #[arg(long, action, conflicts_with_all = ["wrap", "pipeline", "format"])]
+    skip_errors: bool,
+
+    /// Squeeze the spaces inside the lines which are too long, before wrapping or failing.
+    #[arg(long, action)]
+    shrink: bool,
+
+    /// Squeeze the runs of spaces inside the lines into one, before aligning.
+    #[arg(long, action)]
+    squeeze: bool,
+
+    /// Like `fmt -u`: one space between words, and two after sentences, for `align fmt`.
+    #[arg(skip)]
+    uniform_spacing: bool,
+
+    /// Keep the spaces on the right in output.
+    #[arg(short, long, action)]
+    keep: bool,
+
+    /// Offset if line can't be centered perfectly
+    #[arg(value_enum, short, long, default_value_t, ignore_case = true)]
+    bias: Bias,
+
+    /// Place the lines inside the block with a named aligner instead of --inner: 'optical-center' centers them
+    /// without their trailing punctuation, which hangs on the right.
+    #[arg(
+        long,
+        value_name = "NAME",
+        value_parser = parse_aligner,
+        conflicts_with_all = ["inner", "align", "pipeline", "flow", "unalign"]
+    )]
+    aligner: Option<String>,
+
+    /// Place each line so that this fraction of the free space (between 0 and 1) goes before it,
+    /// e.g. 0.3 for 30% before and 70% after.
+    #[arg(
+        long,
+        value_name = "RATIO",
+        value_parser = parse_ratio,
+        conflicts_with_all = ["outer", "inner", "align", "aligner", "pipeline", "flow", "unalign"]
+    )]
+    at: Option<f32>,
+
+    /// Swap left and right, for right-to-left text: in the alignments (those of --remainder, --last-line,
+    /// --pipeline, and --prefixed included) and the bias.
+    #[arg(long, action)]
+    mirror: bool,
+
+    #[command(flatten)]
+    region: RegionArgs,
+
+    /// Steps to apply in order instead (e.g. 'trim | wrap:60 | inner:left | outer:center:80').
+    #[arg(
+        long,
+        value_name = "STEPS",
+        conflicts_with_all = ["outer", "inner", "align", "wrap", "trim"]
+    )]
+    pipeline: Option<Pipeline>,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    #[command(flatten)]
+    flow: FlowArgs,
+
+    /// Align the operators of consecutive lines to the same column before aligning,
+    /// among those given (comma-separated), or '=', ':=', '=>', and ':'.
+    #[arg(
+        long,
+        value_name = "OPERATORS",
+        value_delimiter = ',',
+        num_args = 0..=1,
+        default_missing_value = ":=,=>,=,:"
+    )]
+    on_operator: Option<Vec<String>>,
+
+    /// Use the options for a kind of text (e.g. 'dotenv' to align the values on '='), 'help' to list them.
+    /// The options given take precedence.
+    #[arg(value_enum, long, ignore_case = true)]
+    preset: Option<Preset>,
+
+    /// Align the line-continuation backslashes to the same column before aligning,
+    /// one space after the longest line of each block if 0.
+    #[arg(
+        long,
+        value_name = "COLUMN",
+        num_args = 0..=1,
+        default_missing_value = "0"
+    )]
+    backslashes: Option<usize>,
+
+    /// Pad the timestamps at the start of the lines (ISO 8601 or syslog) so that the messages after them
+    /// start at the same column, before aligning.
+    #[arg(long, action)]
+    timestamps: bool,
+
+    /// Center the text vertically in the terminal, by adding blank lines around it.
+    #[arg(
+        long,
+        visible_alias = "vcenter",
+        action,
+        conflicts_with_all = ["diff", "check"]
+    )]
+    middle: bool,
+
+    /// Remove the padding instead: output left-aligned, trimmed lines.
+    #[arg(
+        long,
+        action,
+        conflicts_with_all = ["outer", "inner", "align", "columns", "wrap", "pipeline", "format"]
+    )]
+    unalign: bool,
+
+    /// How to write out the aligned text.
+    #[arg(value_enum, long, default_value_t, ignore_case = true)]
+    output_format: OutputFormat,
+
+    /// Number of columns taken by the emoji (ZWJ sequences included), 'auto' guessing it from the terminal.
+    /// As given by the Unicode standard if unspecified.
+    #[arg(value_enum, long, value_name = "WIDTH")]
+    emoji_width: Option<EmojiColumns>,
+
+    /// Measure, wrap, and pad the lines in bytes instead of columns, like 'fold -b', e.g. for systems with fixed-length records.
+    #[arg(long, action, conflicts_with = "emoji_width")]
+    bytes: bool,
+
+    /// Show the inserted spaces as '·'.
+    #[arg(long, action)]
+    show_spaces: bool,
+
+    /// Pad the lines with CHAR instead of spaces (e.g. '.' to lead to the page numbers of a table of contents).
+    #[arg(long, value_name = "CHAR", conflicts_with = "show_spaces")]
+    fill: Option<char>,
+
+    /// Color the lines and their padding, to show the block as a solid banner (e.g. 'bg:blue' or 'fg:white,bg:#202040').
+    /// The padding on the right is kept.
+    #[arg(long, value_name = "COLORS", conflicts_with_all = ["output_format", "diff", "check"])]
+    pad_color: Option<PadColor>,
+
+    /// Print statistics about the alignment to stderr.
+    #[arg(long, action)]
+    stats: bool,
+
+    /// Print the byte length, the number of characters, and the width of each line to stderr, side by side,
+    /// to find out why a line doesn't align as expected.
+    #[arg(long, action)]
+    debug_width: bool,
+
+    /// Print the width of the text, its number of lines, and the number of its widest line (e.g. '42 10 3'),
+    /// measured like for aligning (after trimming with --trim), instead of aligning it.
+    #[arg(
+        long,
+        action,
+        conflicts_with_all = ["interactive", "watch", "serve", "stream", "check", "diff", "stats"]
+    )]
+    measure: bool,
+
+    /// Report the progress of reading and writing on stderr.
+    #[arg(long, action)]
+    progress: bool,
+
+    /// Check if the text is already aligned: exit with 1 and print the lines which aren't, 0 otherwise.
+    #[arg(long, action, conflicts_with_all = ["diff", "output_format"])]
+    check: bool,
+
+    #[command(flatten)]
+    pagination: PageArgs,
+
+    /// Read the text from FILE instead of stdin, and redraw it whenever it changes (checked every 200ms) or the terminal is resized.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["diff", "check", "stats", "progress"]
+    )]
+    watch: Option<PathBuf>,
+
+    /// Read the text from the FILEs instead of stdin, aligning each of them separately, one after another.
+    #[arg(value_name = "FILE", conflicts_with_all = ["watch", "interactive", "serve", "gzip"])]
+    files: Vec<PathBuf>,
+
+    /// Align all the FILEs within the same width, the widest one's when the text's width is used (with -c 0).
+    #[arg(long, action, requires = "files")]
+    shared_width: bool,
+
+    /// Take the options of each file (or of the text from stdin) from its modeline, on its first or last line,
+    /// e.g. '# align: center columns=60 wrap', over the defaults but not the options given on the command line.
+    #[arg(long, action, conflicts_with_all = ["watch", "serve", "stream", "interactive", "check"])]
+    modeline: bool,
+
+    /// Preview the alignment, changing the options with keypresses, then print it and the equivalent options.
+    #[arg(
+        long,
+        action,
+        conflicts_with_all = ["watch", "diff", "check", "stats", "output_format", "unalign"]
+    )]
+    interactive: bool,
+
+    #[command(flatten)]
+    serve: ServeArgs,
+
+    /// The path of the file being read from stdin, to use the presets for its type from the config.
+    #[arg(long, value_name = "PATH")]
+    stdin_filepath: Option<PathBuf>,
+
+    /// Leave the lines which the alignment doesn't change as they are, including their trailing spaces.
+    #[arg(long, action)]
+    only_changed: bool,
+
+    /// Fail instead of recovering when the terminal's size is unknown (using the text's size),
+    /// or the input isn't valid UTF-8 (replacing the invalid bytes).
+    #[arg(long, action)]
+    strict: bool,
+
+    /// Log the phases of the alignment on stderr, up to LEVEL (error, warn, info, debug, or trace).
+    #[cfg(feature = "tracing")]
+    #[arg(long, value_name = "LEVEL")]
+    log_level: Option<tracing::Level>,
+
+    /// Print warnings about the input on stderr: hard tabs, control characters, and values cut to fit in --fields.
+    #[arg(long, action)]
+    verbose: bool,
+
+    /// Show the aligned text in a pager ($PAGER, or 'less -S'), 'auto' if WHEN is omitted:
+    /// when stdout is a terminal and the text is taller than it.
+    #[arg(
+        value_enum,
+        long,
+        value_name = "WHEN",
+        num_args = 0..=1,
+        default_value_t,
+        default_missing_value = "auto",
+        conflicts_with_all = ["watch", "interactive", "serve", "check", "progress"]
+    )]
+    pager: Paging,
+
+    #[command(flatten)]
+    stream: StreamArgs,
+
+    /// Decompress the gzipped text read from stdin (files ending with .gz are always decompressed).
+    #[arg(long, action)]
+    gzip: bool,
+
+    #[cfg(feature = "clipboard")]
+    #[command(flatten)]
+    clipboard: ClipboardArgs,
+
+    /// Write to FILE where each input line ended up, as JSON: the spaces inserted before and after it,
+    /// and the output line and position (in characters) of each of its characters, for editors to remap cursors.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["watch", "interactive", "serve", "check", "diff"]
+    )]
+    offsets_json: Option<PathBuf>,
+
+    /// Print a diff of the changes instead of the aligned text.
+    #[arg(long, action, conflicts_with = "output_format")]
+    diff: bool,
+}
+
+impl Args {
+    /// Parses the options like those of the command line (without the program's name), and applies the shorthands
+    /// (`--align`, `--preset`, and `--mirror`) like `align` does, but not the defaults of the config.
+    ///
+    /// # Example
+    /// ```
+    /// use align_cli::{align_lines, Args};
+    ///
+    /// let args = Args::from_options(["--align", "right", "--mirror"]).unwrap();
+    /// let lines = vec!["a".to_string(), "bcd".to_string()];
+    /// assert_eq!(align_lines(lines, &args, None).unwrap().render(false), vec!["a", "bcd"]);
+    ///
+    /// assert!(Args::from_options(["--columns", "wide"]).is_err());
+    /// ```
+    pub fn from_options<I, T>(options: I) -> Result<Args, Failure>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let matches = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(options)
+            .map_err(|e| Failure::Usage(e.to_string()))?;
+        Args::from_matches(&matches)
+    }
+
+    /// Takes the args from already parsed options, see [`Args::from_options()`].
+    fn from_matches(matches: &ArgMatches) -> Result<Args, Failure> {
+        let mut args =
+            Args::from_arg_matches(matches).map_err(|e| Failure::Usage(e.to_string()))?;
+        args.apply_shorthands(matches);
+        Ok(args)
+    }
+
+    /// Sets the alignments of `--align`, the options of `--preset` which weren't `given`, and mirrors them with `--mirror`.
+    fn apply_shorthands(&mut self, given: &ArgMatches) {
+        if let Some(wh) = self.align.clone() {
+            self.outer = wh.clone();
+            self.inner = wh;
+        }
+
+        if let Some(preset) = self.preset {
+            preset.apply(self, given);
+        }
+
+        if self.mirror {
+            mirror(self);
+        }
+    }
+}
+
+/// How the lines which are too long are wrapped.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum WrapMode {
+    /// Split the lines at the number of columns.
+    Anywhere,
+    /// Split the lines between words, only breaking the words which are too long.
+    OverflowOnly,
+}
+
+/// How the words which are wider than the columns are split, see [`WordSplitter`].
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Splitter {
+    /// Leave them whole, wider than the columns.
+    Never,
+    /// Split them at the number of columns.
+    #[default]
+    AtWidth,
+    /// Split them one column before, and end each piece with a hyphen.
+    Hyphenate,
+}
+
+impl From<Splitter> for WordSplitter {
+    fn from(splitter: Splitter) -> Self {
+        match splitter {
+            Splitter::Never => WordSplitter::Never,
+            Splitter::AtWidth => WordSplitter::AtWidth,
+            Splitter::Hyphenate => WordSplitter::Hyphenate,
+        }
+    }
+}
+
+/// How the control characters are shown, see [`Placeholders`].
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Reveal {
+    /// '^M', like `cat -v`.
+    Caret,
+    /// '␍', the Unicode control pictures.
+    Pictures,
+}
+
+impl From<Reveal> for Placeholders {
+    fn from(reveal: Reveal) -> Self {
+        match reveal {
+            Reveal::Caret => Placeholders::Caret,
+            Reveal::Pictures => Placeholders::Pictures,
+        }
+    }
+}
+
+/// The Unicode normalization form of --normalize, see [`NormalizationForm`].
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Normalization {
+    /// Composed characters.
+    Nfc,
+    /// Composed characters, the compatibility ones being replaced by their plain equivalents (e.g. 'ﬁ' by 'fi').
+    Nfkc,
+}
+
+impl From<Normalization> for NormalizationForm {
+    fn from(normalization: Normalization) -> Self {
+        match normalization {
+            Normalization::Nfc => NormalizationForm::Nfc,
+            Normalization::Nfkc => NormalizationForm::Nfkc,
+        }
+    }
+}
+
+/// What to do with the line separators inside the lines, see [`LineSeparators`].
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Separators {
+    /// Split the lines at them.
+    #[default]
+    Split,
+    /// Remove them.
+    Strip,
+    /// Fail, showing the line.
+    Error,
+}
+
+impl From<Separators> for LineSeparators {
+    fn from(separators: Separators) -> Self {
+        match separators {
+            Separators::Split => LineSeparators::Split,
+            Separators::Strip => LineSeparators::Strip,
+            Separators::Error => LineSeparators::Error,
+        }
+    }
+}
+
+/// How many columns the emoji take up in the terminal.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum EmojiColumns {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    Auto,
+}
+
+impl EmojiColumns {
+    /// The width of the emoji, guessed from the environment with `auto`:
+    /// the terminals known to draw the emoji sequences as 2 columns, and the Linux console as 1.
+    /// It's left to the Unicode standard for the other terminals.
+    fn width(&self) -> EmojiWidth {
+        const WIDE: [&str; 6] = ["kitty", "wezterm", "ghostty", "foot", "iterm", "vscode"];
+
+        match self {
+            EmojiColumns::One => EmojiWidth::Narrow,
+            EmojiColumns::Two => EmojiWidth::Wide,
+            EmojiColumns::Auto => {
+                let term = std::env::var("TERM").unwrap_or_default().to_lowercase();
+                let program = std::env::var("TERM_PROGRAM")
+                    .unwrap_or_default()
+                    .to_lowercase();
+
+                if term == "linux" {
+                    EmojiWidth::Narrow
+                } else if WIDE
+                    .iter()
+                    .any(|name| term.contains(name) || program.contains(name))
+                {
+                    EmojiWidth::Wide
+                } else {
+                    EmojiWidth::Unicode
+                }
+            }
+        }
+    }
+}
+
+/// The options measuring the text like the lines being aligned (see --emoji-width and --bytes),
+/// for the widths computed outside of the alignment (e.g. of the comment markers, or when streaming).
+fn measuring(args: &Args) -> Options {
+    Options {
+        emoji_width: args
+            .emoji_width
+            .map_or(EmojiWidth::Unicode, |emoji_width| emoji_width.width()),
+        width_unit: match args.bytes {
+            true => WidthUnit::Bytes,
+            false => WidthUnit::Columns,
+        },
+        ..Default::default()
+    }
+}
+
+/// The layout of --fields, split and measured as the args say.
+fn record_layout(args: &Args) -> Option<RecordLayout> {
+    let mut fields = args.table.fields.clone()?;
+    fields.delimiter = args.table.field_delimiter;
+    let measuring = measuring(args);
+    fields.emoji_width = measuring.emoji_width;
+    fields.width_unit = measuring.width_unit;
+    Some(fields)
+}
+
+/// Bundles of options for common kinds of text.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Preset {
+    /// `KEY = value` lines, aligned on `=`.
+    Dotenv,
+    /// `key: value` lines, aligned on `:`.
+    Yaml,
+    /// A title, trimmed, centered, and wrapped between words.
+    Banner,
+    /// Quoted text (starting with '>'), reflowed between its quote markers.
+    Quote,
+    /// An e-mail, reflowed within 72 columns, without breaking its quotes and URLs.
+    Email,
+    /// Code comments, reflowed after their '//' markers (or those of --comment).
+    CodeComment,
+    /// Whitespace-separated columns, each padded to its widest field, the numbers on the right.
+    Table,
+    /// List the presets.
+    #[value(hide = true)]
+    Help,
+}
+
+impl Preset {
+    /// Sets the options of the preset which weren't given.
+    /// With `dotenv` and `yaml`, the text isn't padded (unless --columns is given), and the comments, blank lines,
+    /// and quoted values are left as they are.
+    fn apply(&self, args: &mut Args, matches: &ArgMatches) {
+        let given = |id: &str| {
+            matches.try_contains_id(id).is_ok()
+                && matches.value_source(id) == Some(ValueSource::CommandLine)
+        };
+        let reflow = |args: &mut Args| {
+            args.reflow = true;
+            args.wrap.get_or_insert(WrapMode::OverflowOnly);
+        };
+
+        match self {
+            Preset::Dotenv | Preset::Yaml => {
+                let operator = if *self == Preset::Dotenv { "=" } else { ":" };
+                args.on_operator
+                    .get_or_insert_with(|| vec![operator.to_string()]);
+                args.columns.get_or_insert(0);
+            }
+            Preset::Banner => {
+                if !given("align") && !given("outer") && !given("inner") {
+                    args.outer = Where::Center;
+                    args.inner = Where::Center;
+                }
+                args.trim = true;
+                args.wrap.get_or_insert(WrapMode::OverflowOnly);
+            }
+            Preset::Quote => {
+                args.quotes = true;
+                reflow(args);
+            }
+            Preset::Email => {
+                args.columns.get_or_insert(72);
+                args.quotes = true;
+                args.keep_urls = true;
+                reflow(args);
+            }
+            Preset::CodeComment => {
+                args.comment.get_or_insert_with(|| "//".to_string());
+                reflow(args);
+            }
+            Preset::Table => {
+                args.columns.get_or_insert(0);
+            }
+            Preset::Help => {}
+        }
+    }
+
+    /// The presets and what they do, for `--preset help`.
+    fn list() -> String {
+        Preset::value_variants()
+            .iter()
+            .filter_map(|preset| preset.to_possible_value())
+            .filter(|value| !value.is_hide_set())
+            .map(|value| {
+                let help = value
+                    .get_help()
+                    .map(|help| help.to_string())
+                    .unwrap_or_default();
+                format!("{:<14}{help}\n", value.get_name())
+            })
+            .collect()
+    }
+}
+
+/// The format padding each whitespace-separated column of the lines to its widest field, for `--preset table`.
+fn table_format(lines: &[String], measuring: &Options) -> LineFormat {
+    let mut widths = vec![];
+    for line in lines {
+        for (index, field) in line.split_whitespace().enumerate() {
+            let width = measuring.width_of(field);
+            match widths.get_mut(index) {
+                Some(widest) => *widest = width.max(*widest),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    let mut fields = vec![];
+    for (index, width) in widths.into_iter().enumerate() {
+        if index > 0 {
+            fields.push(Field::Literal("  ".to_string()));
+        }
+        fields.push(Field::Column { align: None, width });
+    }
+    LineFormat {
+        fields,
+        auto_numeric: true,
+        decimal_separator: '.',
+        emoji_width: measuring.emoji_width,
+        width_unit: measuring.width_unit,
+    }
+}
+
+/// The subcommands, whose options are turned into [`Args`].
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Align a block of text, the same as without a subcommand.
+    Block(Box<Args>),
+    /// Wrap the lines which are too long to fit in the columns, and align them.
+    Wrap(subcommand::Wrap),
+    /// Line up the fields of the lines (formatted, fixed-width, or around an operator), and align them.
+    Table(subcommand::Table),
+    /// Flow the lines into N columns side by side, like a newspaper, and align them.
+    Grid(subcommand::Grid),
+    /// Check if the text is already aligned: exit with 1 and print the lines which aren't, 0 otherwise.
+    Check(subcommand::Check),
+    /// Reflow the paragraphs like fmt(1), taking its options, e.g. 'align fmt -w 72 -p "# "'.
+    Fmt(subcommand::Fmt),
+    /// Print the completions script for a shell.
+    #[command(hide = true)]
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+fn get_terimnal_width() -> Result<usize, String> {
+    term_size::dimensions()
+        .map(|(width, _height)| width)
+        .ok_or("couldn't get terminal width".to_string())
+}
+
+fn get_terminal_height() -> Result<usize, String> {
+    term_size::dimensions()
+        .map(|(_width, height)| height)
+        .ok_or("couldn't get terminal height".to_string())
+}
+
+/// The terminal width, if it's needed to deduce the number of columns.
+fn get_needed_terminal_width(args: &Args) -> Result<Option<usize>, String> {
+    // the steps of --pipeline only need it for the outer ones without columns
+    let pipelined = args.pipeline.as_ref().is_some_and(|pipeline| {
+        !pipeline
+            .steps
+            .iter()
+            .any(|step| matches!(step, Step::Outer(_, None)))
+    });
+    match args.columns {
+        None if !args.unalign && !args.measure && !pipelined => match get_terimnal_width() {
+            Ok(width) => Ok(Some(width)),
+            Err(e) if args.strict => Err(e),
+            Err(e) => {
+                eprintln!("warning: {e}, using the text's width");
+                Ok(None)
+            }
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Deduces the final number of columns depending on args.
+fn get_cols_wrap(args: &Args, terminal_width: Option<usize>) -> Option<(usize, bool)> {
+    match (args.columns, terminal_width) {
+        (Some(0), _) => None,
+        (Some(c), _) => Some((c, args.wrap.is_some())),
+        (None, width) => width.map(|width| {
+            let width = match args.snap {
+                Some(snap) if width >= snap.get() => width / snap * snap.get(),
+                _ => width,
+            };
+            (width, args.wrap.is_some())
+        }),
+    }
+}
+
+/// The text's width rounded up to a multiple of --snap.
+fn snapped(width: usize, args: &Args) -> usize {
+    match args.snap {
+        Some(snap) => width.div_ceil(snap.get()) * snap.get(),
+        None => width,
+    }
+}
+
+/// Puts back the input lines which are the same after aligning (apart from the trailing spaces),
+/// so they're output byte-identical.
+fn keep_unchanged(input: &[String], layout: &mut Layout, keep: bool) {
+    let mut counts = vec![0; input.len()];
+    for line in &layout.lines {
+        counts[line.origin] += 1;
+    }
+
+    for line in layout.lines.iter_mut() {
+        let original = &input[line.origin];
+        // the wrapped lines changed
+        if counts[line.origin] == 1 && line.render(keep).trim_end() == original.trim_end() {
+            *line = LineLayout {
+                text: original.clone(),
+                origin: line.origin,
+                range: 0..original.len(),
+                before: 0,
+                after: 0,
+            };
+        }
+    }
+}
+
+/// Prints the width of the text, its number of lines, and the number of its widest line, for --measure.
+fn print_measure(lines: &[String], trim: bool, measuring: &Options) {
+    let metrics = if trim {
+        measure_with(
+            &lines
+                .iter()
+                .map(|line| line.trim().to_string())
+                .collect::<Vec<String>>(),
+            measuring,
+        )
+    } else {
+        measure_with(lines, measuring)
+    };
+    let line = metrics.longest_line_index.map_or(0, |index| index + 1);
+    println!("{} {} {line}", metrics.width, metrics.height);
+}
+
+/// Centers the lines vertically in the terminal.
+fn place_middle(layout: &mut Layout, args: &Args) -> Result<(), String> {
+    let rows = match get_terminal_height() {
+        Ok(height) => height.max(layout.lines.len()),
+        Err(e) if args.strict => return Err(e),
+        Err(e) => {
+            eprintln!("warning: {e}, not centering vertically");
+            layout.lines.len()
+        }
+    };
+    layout
+        .place_vertically(rows, VWhere::Middle, false, args.bias)
+        .map_err(|e| e.to_string())
+}
+
+/// Reads the file, decompressing it if its extension is `.gz`.
+fn read_text(path: &Path, strict: bool) -> Result<Vec<String>, String> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("read", path = %path.display()).entered();
+    let error = |e: std::io::Error| format!("couldn't read {}: {e}", path.display());
+    let mut text = std::fs::read(path).map_err(error)?;
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        let mut decompressed = vec![];
+        MultiGzDecoder::new(text.as_slice())
+            .read_to_end(&mut decompressed)
+            .map_err(error)?;
+        text = decompressed;
+    }
+
+    decode(text, strict, path.display())?
+        .lines()
+        .map(|line| Ok(line.to_string()))
+        .collect()
+}
+
+/// Removes the file at `path` and exits when the process is interrupted or terminated (SIGINT, SIGTERM, or SIGHUP),
+/// for the files which would be left behind otherwise, the usual cleanup when they're dropped not running then.
+#[cfg(unix)]
+fn remove_on_signal(path: &Path) -> Result<(), String> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    use signal_hook::iterator::Signals;
+
+    let mut signals = Signals::new([SIGHUP, SIGINT, SIGTERM]).map_err(|e| e.to_string())?;
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            let _ = std::fs::remove_file(&path);
+            std::process::exit(128 + signal);
+        }
+    });
+    Ok(())
+}
+
+/// Tells how to end the text when it's typed in the terminal, rather than piped in,
+/// so that waiting for it doesn't look like hanging.
+fn hint_if_typed() {
+    if stdin().is_terminal() {
+        let end = if cfg!(windows) {
+            "Ctrl-Z then Enter"
+        } else {
+            "Ctrl-D"
+        };
+        eprintln!("Type or paste the text, then press {end} on an empty line to align it (see align --help).");
+    }
+}
+
+/// Reads stdin, decompressing it if `gzip`.
+fn get_text(progress: &mut Progress, strict: bool, gzip: bool) -> Result<Vec<String>, String> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("read", gzip).entered();
+    hint_if_typed();
+    let mut input: Box<dyn BufRead> = if gzip {
+        Box::new(BufReader::new(MultiGzDecoder::new(stdin().lock())))
+    } else {
+        Box::new(stdin().lock())
+    };
+    let mut lines = vec![];
+
+    loop {
+        let mut line = vec![];
+        let read = input.read_until(b'\n', &mut line).map_err(|e| match gzip {
+            true => format!("couldn't decompress stdin: {e}"),
+            false => e.to_string(),
+        })?;
+        if read == 0 {
+            break;
+        }
+        progress.tick(read);
+
+        if line.ends_with(b"\n") {
+            line.pop();
+            if line.ends_with(b"\r") {
+                line.pop();
+            }
+        }
+        lines.push(decode(line, strict, format!("line {}", lines.len() + 1))?);
+    }
+
+    progress.finish();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(lines = lines.len(), "read stdin");
+    Ok(lines)
+}
+
+/// Handles the line separators inside the lines (e.g. lone `\r`) according to --line-separators.
+fn normalize_lines(lines: Vec<String>, args: &Args) -> Result<Vec<String>, String> {
+    if !lines.iter().any(|line| line.contains(LINE_SEPARATORS)) {
+        return Ok(lines);
+    }
+
+    normalize_separators(&lines, args.line_separators.into()).map_err(|e| match e {
+        // the separators aren't visible
+        Error::StraySeparator { line_index } => {
+            format!("{e}:\n{}", lines[line_index].escape_debug())
+        }
+        e => e.to_string(),
+    })
+}
+
+/// Prints the byte length, the number of characters, and the width of each line to stderr, for --debug-width.
+fn debug_widths(lines: &[String], measuring: &Options) {
+    let headers = ["line", "bytes", "chars", "width"]
+        .map(String::from)
+        .to_vec();
+    let rows = std::iter::once(headers)
+        .chain(lines.iter().enumerate().map(|(index, line)| {
+            vec![
+                (index + 1).to_string(),
+                line.len().to_string(),
+                line.chars().count().to_string(),
+                measuring.width_of(line).to_string(),
+            ]
+        }))
+        .collect::<Vec<Vec<String>>>();
+    let aligns = [Where::Right, Where::Right, Where::Right, Where::Right];
+
+    let texts = std::iter::once("text").chain(lines.iter().map(String::as_str));
+    for (cells, text) in align_cells(&rows, &aligns, Bias::Left).iter().zip(texts) {
+        eprintln!("{}  {text}", cells.join(" "));
+    }
+}
+
+/// Prints the issues with the lines which may make the result look wrong, prefixed with the `path` of their file if there's one.
+fn warn_about(lines: &[String], args: &Args, path: Option<&Path>) {
+    let mut diagnostics = diagnose(lines);
+    if let Some(fields) = record_layout(args) {
+        // the errors are reported when aligning
+        if let Ok((_, truncated)) = fields.format_with_diagnostics(lines) {
+            diagnostics.extend(truncated);
+        }
+    }
+    diagnostics.sort_by_key(Diagnostic::line_index);
+
+    for diagnostic in diagnostics {
+        match path {
+            Some(path) => eprintln!("warning: {}: {diagnostic}", path.display()),
+            None => eprintln!("warning: {diagnostic}"),
+        }
+    }
+}
+
+/// Decodes the text as UTF-8, replacing the invalid bytes unless `strict`.
+fn decode(bytes: Vec<u8>, strict: bool, location: impl Display) -> Result<String, String> {
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(_) if strict => Err(format!("invalid UTF-8 in {location}")),
+        Err(e) => Ok(String::from_utf8_lossy(e.as_bytes()).to_string()),
+    }
+}
+
+/// Checks that there's an aligner named `name`, for --aligner.
+fn parse_aligner(name: &str) -> Result<String, String> {
+    let aligners = Aligners::default();
+    match aligners.get(name) {
+        Some(_) => Ok(name.to_string()),
+        None => Err(format!(
+            "unknown aligner, the aligners are: {}",
+            aligners.names().collect::<Vec<&str>>().join(", ")
+        )),
+    }
+}
+
+/// Compiles the regular expressions of the markers of --between.
+fn parse_regex(input: &str) -> Result<Regex, String> {
+    Regex::new(input).map_err(|e| e.to_string())
+}
+
+/// Checks that the ratio of --at is between 0 and 1.
+fn parse_ratio(input: &str) -> Result<f32, String> {
+    match input.parse::<f32>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+        Ok(_) => Err("the ratio must be between 0 and 1".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The opposite side, for --mirror.
+fn mirrored(wh: &Where) -> Where {
+    match wh {
+        Where::Left => Where::Right,
+        Where::Center => Where::Center,
+        Where::Right => Where::Left,
+    }
+}
+
+/// Swaps left and right in the alignments and the bias.
+fn mirror(args: &mut Args) {
+    args.outer = mirrored(&args.outer);
+    args.inner = mirrored(&args.inner);
+    args.remainder = args.remainder.as_ref().map(mirrored);
+    args.last_line = args.last_line.as_ref().map(mirrored);
+    args.at = args.at.map(|at| 1.0 - at);
+    args.bias = match args.bias {
+        Bias::Left => Bias::Right,
+        Bias::Right => Bias::Left,
+        Bias::Alternate => Bias::Alternate,
+    };
+
+    if let Some(pipeline) = &mut args.pipeline {
+        for step in pipeline.steps.iter_mut() {
+            match step {
+                Step::Inner(wh) | Step::Outer(wh, _) => *wh = mirrored(wh),
+                Step::Trim | Step::Wrap(_) => {}
+            }
+        }
+    }
+}
+
+/// Removes the alignment prefixes (`<` left, `^` center, `>` right) at the start of the lines,
+/// and returns the alignment of each line.
+fn strip_align_prefixes(lines: &mut [String]) -> Vec<Option<Where>> {
+    lines
+        .iter_mut()
+        .map(|line| {
+            let align = match line.chars().next() {
+                Some('<') => Where::Left,
+                Some('^') => Where::Center,
+                Some('>') => Where::Right,
+                _ => return None,
+            };
+            line.remove(0);
+            Some(align)
+        })
+        .collect()
+}
+
+/// Aligns the text of each file separately with `align`, one after another.
+/// With --shared-width, they're all aligned within the widest one's width when the text's width is used.
+fn align_files(
+    files: &[Vec<String>],
+    args: &Args,
+    cols_wrap: Option<(usize, bool)>,
+    align: impl Fn(&[String], Option<(usize, bool)>) -> Result<Layout, String>,
+) -> Result<Layout, String> {
+    let named = |index: usize, e: String| format!("{}: {e}", args.files[index].display());
+    let mut layouts = files
+        .iter()
+        .enumerate()
+        .map(|(index, lines)| align(lines, cols_wrap).map_err(|e| named(index, e)))
+        .collect::<Result<Vec<Layout>, String>>()?;
+
+    if args.shared_width && cols_wrap.is_none() {
+        let columns = layouts
+            .iter()
+            .map(|layout| layout.columns)
+            .max()
+            .unwrap_or_default();
+        for (index, layout) in layouts.iter_mut().enumerate() {
+            if layout.columns < columns {
+                *layout =
+                    align(&files[index], Some((columns, false))).map_err(|e| named(index, e))?;
+            }
+        }
+    }
+
+    let mut aligned = Layout::default();
+    let mut offset = 0;
+    for (lines, layout) in files.iter().zip(layouts) {
+        aligned.columns = aligned.columns.max(layout.columns);
+        aligned
+            .lines
+            .extend(layout.lines.into_iter().map(|mut line| {
+                line.origin += offset;
+                line
+            }));
+        offset += lines.len();
+    }
+
+    Ok(aligned)
+}
+
+/// Aligns the lines with the options of their modeline (see [`Defaults::find_modeline()`]) instead of the defaults,
+/// leaving the modeline as it is. The columns are those of `cols_wrap` if the args don't change them.
+fn align_with_modeline(
+    lines: &[String],
+    args: &Args,
+    given: &ArgMatches,
+    terminal_width: Option<usize>,
+    cols_wrap: Option<(usize, bool)>,
+) -> Result<Layout, String> {
+    let Some((index, defaults)) = Defaults::find_modeline(lines)? else {
+        return align_input(lines.to_vec(), args, cols_wrap);
+    };
+
+    let defaults = match args.mirror {
+        true => defaults.mirrored(),
+        false => defaults,
+    };
+    let mut own = args.clone();
+    let _ = defaults.apply(&mut own, given)?;
+    let cols_wrap = match own.columns == args.columns && own.wrap == args.wrap {
+        true => cols_wrap,
+        false => get_cols_wrap(&own, terminal_width),
+    };
+
+    let mut kept = (0..lines.len()).collect::<Vec<usize>>();
+    kept.remove(index);
+    let mut rest = lines.to_vec();
+    let modeline = rest.remove(index);
+    let layout = align_input(rest, &own, cols_wrap)?;
+    Ok(restore_skipped(layout, &kept, vec![(index, modeline)]))
+}
+
+/// Aligns the lines as one block, according to the args.
+pub fn align_lines(
+    lines: Vec<String>,
+    args: &Args,
+    cols_wrap: Option<(usize, bool)>,
+) -> Result<Layout, Error> {
+    if args.unalign {
+        return Ok(Layout::from(unalign(&lines, ' ')));
+    }
+
+    let (lines, aligns) = prepare_lines(lines, args)?;
+
+    if let Some(pipeline) = &args.pipeline {
+        let mut pipeline = pipeline.clone();
+        pipeline.bias = args.bias;
+        pipeline.keep_spaces = args.keep;
+        pipeline.emoji_width = measuring(args).emoji_width;
+        pipeline.width_unit = measuring(args).width_unit;
+
+        // outer steps without columns use the terminal's (or specified) number of columns
+        for step in pipeline.steps.iter_mut() {
+            if let Step::Outer(_, columns @ None) = step {
+                *columns = cols_wrap.map(|(num, _)| num);
+            }
+        }
+
+        return pipeline.layout(&lines);
+    }
+
+    let options = line_options(args, cols_wrap);
+
+    if let Some(count) = args.flow.count {
+        // each column gets its share of the columns, minus the gutters
+        let gutters = args.flow.gutter * (count.get() - 1);
+        let column_options = Options {
+            align: args.inner.clone(),
+            columns: options
+                .columns
+                .map(|num| num.saturating_sub(gutters) / count),
+            // the lines must all have the same width to be aligned together
+            keep_spaces: true,
+            ..options.clone()
+        };
+        let flowed = flow_layout(
+            &lines,
+            count,
+            args.flow.rows,
+            args.flow.gutter,
+            &column_options,
+        )?;
+
+        let outer_options = Options {
+            align: args.outer.clone(),
+            remainder: None,
+            last_line: None,
+            trim: false,
+            shrink: false,
+            squeeze: false,
+            ..options
+        };
+        let mut result = layout(&flowed.render(false), &outer_options)?;
+        // each line comes from the line it starts with
+        for line in result.lines.iter_mut() {
+            let row = &flowed.lines[line.origin];
+            line.origin = row.origin;
+            line.range = row.range.clone();
+        }
+        if !args.keep {
+            result.trim_end();
+        }
+        return Ok(result);
+    }
+
+    if let Some(at) = args.at {
+        // the lines are placed within all the columns, not a block
+        let mut layout = layout(
+            &lines,
+            &Options {
+                remainder: None,
+                last_line: None,
+                ..options
+            },
+        )?;
+        let aligns = aligns.unwrap_or_default();
+        layout.realign_with(
+            &Prefixed {
+                aligns,
+                inner: &At(at),
+            },
+            args.bias,
+        );
+        if let Some(remainder) = &args.remainder {
+            layout.realign_continuations(remainder, args.bias);
+        }
+        if let Some(last_line) = &args.last_line {
+            layout.realign_last_lines(last_line, args.bias);
+        }
+        return Ok(layout);
+    }
+
+    let aligners = Aligners::default();
+    let aligner = args.aligner.as_deref().and_then(|name| aligners.get(name));
+    if args.outer == Where::Center
+        && args.inner == Where::Center
+        && aligner.is_none()
+        && aligns.is_some()
+    {
+        // center completely, each line its own way
+        let options = Options {
+            align: Where::Center,
+            ..options
+        };
+        let aligns = aligns
+            .unwrap_or_default()
+            .into_iter()
+            .map(|align| align.unwrap_or(Where::Center))
+            .collect::<Vec<Where>>();
+        return align_each(&lines, &aligns, &options);
+    }
+
+    let options = Options {
+        align: args.outer.clone(),
+        ..options
+    };
+    match (aligns, aligner) {
+        (None, None) => align_block(&lines, &args.inner, &options),
+        (aligns, aligner) => {
+            let prefixed = Prefixed {
+                aligns: aligns.unwrap_or_default(),
+                inner: aligner.unwrap_or(&args.inner),
+            };
+            align_block_with(&lines, &prefixed, &options)
+        }
+    }
+}
+
+/// Changes the lines as the args say before they're aligned (e.g. with --format or --on-operator),
+/// and takes their own alignments off their prefixes with --prefixed.
+#[allow(clippy::type_complexity)]
+fn prepare_lines(
+    mut lines: Vec<String>,
+    args: &Args,
+) -> Result<(Vec<String>, Option<Vec<Option<Where>>>), Error> {
+    if let Some(form) = args.normalize {
+        lines = normalize_unicode(&lines, form.into());
+    }
+    if let Some(style) = args.reveal {
+        lines = reveal(&lines, style.into());
+    }
+    if args.escape {
+        lines = escape_non_ascii(&lines);
+    }
+
+    // lines' own alignments, from their prefixes
+    let aligns = if args.prefixed {
+        let mut aligns = strip_align_prefixes(&mut lines);
+        if args.mirror {
+            for align in aligns.iter_mut().flatten() {
+                *align = mirrored(align);
+            }
+        }
+        Some(aligns)
+    } else {
+        None
+    };
+
+    if args.dedent {
+        lines = dedent(&lines);
+    }
+
+    let table =
+        (args.preset == Some(Preset::Table)).then(|| table_format(&lines, &measuring(args)));
+    if let Some(format) = args.table.format.as_ref().or(table.as_ref()) {
+        let mut format = format.clone();
+        format.auto_numeric = !args.table.no_auto_numeric;
+        if args.table.decimal_comma {
+            format.decimal_separator = ',';
+        }
+        let measuring = measuring(args);
+        format.emoji_width = measuring.emoji_width;
+        format.width_unit = measuring.width_unit;
+        lines = format.format(&lines, args.bias);
+    }
+
+    if let Some(fields) = record_layout(args) {
+        lines = fields.format(&lines)?;
+    }
+
+    if let Some(column) = args.backslashes {
+        // 0 means after the longest line
+        lines = align_backslashes(&lines, Some(column).filter(|column| *column > 0));
+    }
+
+    if args.timestamps {
+        lines = align_timestamps(&lines);
+    }
+
+    if let Some(operators) = &args.on_operator {
+        let operators = operators.iter().map(String::as_str).collect::<Vec<&str>>();
+        lines = align_operators(&lines, &operators);
+    }
+
+    Ok((lines, aligns))
+}
+
+/// The options the lines are aligned with, before the alignments are set.
+fn line_options(args: &Args, cols_wrap: Option<(usize, bool)>) -> Options {
+    let mut unbreakable = args.unbreakable.clone();
+    if args.keep_urls {
+        unbreakable.extend([URL_PATTERN.to_string(), PATH_PATTERN.to_string()]);
+    }
+    if args.markdown {
+        unbreakable.extend(MARKDOWN_PATTERNS.map(String::from));
+    }
+
+    Options {
+        remainder: args.remainder.clone(),
+        unbreakable,
+        last_line: args.last_line.clone(),
+        shrink: args.shrink,
+        squeeze: args.squeeze,
+        overflow: match (cols_wrap, args.wrap) {
+            (Some((_, true)), Some(WrapMode::OverflowOnly)) => Overflow::WordWrap,
+            (Some((_, true)), _) => Overflow::Wrap,
+            _ => Overflow::Error,
+        },
+        word_splitter: args.word_splitter.into(),
+        subsequent_prefix: args.continuation.clone().unwrap_or_default(),
+        wrap_marker: args.wrap_marker.clone().unwrap_or_default(),
+        tab_width: args.tab_width.map(NonZeroUsize::get),
+        emoji_width: measuring(args).emoji_width,
+        width_unit: measuring(args).width_unit,
+        ..Options::new(Where::Left, cols_wrap, args.trim, args.bias, args.keep)
+    }
+}
+
+/// Aligns the lines with an alignment prefix their own way, and the others with `inner`.
+struct Prefixed<'a> {
+    aligns: Vec<Option<Where>>,
+    inner: &'a dyn LineAligner,
+}
+
+impl LineAligner for Prefixed<'_> {
+    fn spaces_before(&self, text: &str, origin: usize, space: usize, bias: Bias) -> usize {
+        match self.aligns.get(origin) {
+            Some(Some(align)) => align.spaces_before(text, origin, space, bias),
+            _ => self.inner.spaces_before(text, origin, space, bias),
+        }
+    }
+}
+
+/// Fails with the locations of the hard tabs in the lines, if there are any.
+fn forbid_tabs(lines: &[String]) -> Result<(), String> {
+    let locations = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            line.match_indices('\t').map(move |(byte, _)| {
+                let column = line[..byte].chars().count() + 1;
+                format!("line {} column {column}", index + 1)
+            })
+        })
+        .collect::<Vec<String>>();
+
+    if locations.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "the text contains hard tabs, at {}",
+            locations.join(", ")
+        ))
+    }
+}
+
+/// Aligns the input lines within the columns of --width-percentile or --max-columns,
+/// or rounding the text's width up to a multiple of --snap.
+pub fn align_input(
+    lines: Vec<String>,
+    args: &Args,
+    cols_wrap: Option<(usize, bool)>,
+) -> Result<Layout, String> {
+    if args.width_percentile.is_some() || args.max_columns.is_some() {
+        let percentile = args.width_percentile.unwrap_or(100);
+        let width = if args.trim {
+            let trimmed = lines
+                .iter()
+                .map(|line| line.trim().to_string())
+                .collect::<Vec<String>>();
+            width_percentile_with(&trimmed, percentile, &measuring(args))
+        } else {
+            width_percentile_with(&lines, percentile, &measuring(args))
+        };
+        let width = args.max_columns.map_or(width, |max| width.min(max.get()));
+        // the terminal's width is still the limit
+        let columns = cols_wrap.map_or(width, |(num, _)| num.min(width)).max(1);
+        return align_pages(lines, args, Some((columns, true)));
+    }
+    if args.snap.is_none() || cols_wrap.is_some() {
+        return align_pages(lines, args, cols_wrap);
+    }
+
+    // the text's width is only known once it's aligned
+    let layout = align_pages(lines.clone(), args, None)?;
+    let columns = snapped(layout.columns, args);
+    if columns == layout.columns {
+        return Ok(layout);
+    }
+    align_pages(lines, args, Some((columns, false)))
+}
+
+/// Aligns the input lines, each page separately with --pages.
+fn align_pages(
+    lines: Vec<String>,
+    args: &Args,
+    cols_wrap: Option<(usize, bool)>,
+) -> Result<Layout, String> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("align", lines = lines.len()).entered();
+
+    if args.forbid_tabs {
+        forbid_tabs(&lines)?;
+    }
+
+    let input = lines.clone();
+    // show the line, it's hard to find otherwise
+    let show_line = |e: Error| match e {
+        Error::InsufficientColumns { line_index, .. } if line_index < input.len() => {
+            format!("{e}:\n{}", input[line_index])
+        }
+        e => e.to_string(),
+    };
+
+    if !args.pagination.pages {
+        return align_selected(lines, args, cols_wrap).map_err(show_line);
+    }
+
+    let mut layout = Layout::default();
+    for (index, (origins, page)) in split_pages(lines).into_iter().enumerate() {
+        let mut page_layout = align_selected(page, args, cols_wrap)
+            .map_err(|e| show_line(e.map_line_index(|line_index| origins[line_index].0)))?;
+        if let Some(rows) = args.pagination.page_height {
+            page_layout
+                .place_vertically(rows, VWhere::Top, false, args.bias)
+                .map_err(|e| e.to_string())?;
+        }
+
+        if index > 0 {
+            layout.lines.push(LineLayout {
+                text: "\x0c".to_string(),
+                origin: origins.first().map_or(0, |(origin, _)| *origin),
+                range: 0..0,
+                before: 0,
+                after: 0,
+            });
+        }
+        for mut line in page_layout.lines {
+            let (origin, start) = origins.get(line.origin).copied().unwrap_or_default();
+            line.origin = origin;
+            line.range = start + line.range.start..start + line.range.end;
+            layout.lines.push(line);
+        }
+        layout.columns = layout.columns.max(page_layout.columns);
+    }
+
+    Ok(layout)
+}
+
+/// The lines of a page, after where each one comes from: the index of its input line, and the byte it starts at.
+type Page = (Vec<(usize, usize)>, Vec<String>);
+
+/// Splits the lines into pages at the form feeds, with the index of the input line each page's line comes from,
+/// and the byte it starts at in it. The text before a form feed ends the page, and the text after it starts the next one.
+fn split_pages(lines: Vec<String>) -> Vec<Page> {
+    let mut pages = vec![(vec![], vec![])];
+
+    for (index, line) in lines.into_iter().enumerate() {
+        let mut parts = line.split('\x0c').peekable();
+        let mut start = 0;
+        while let Some(part) = parts.next() {
+            let last = parts.peek().is_none();
+            // a form feed alone on its line doesn't leave blank lines around it
+            if !part.is_empty() || (last && line.len() == part.len()) {
+                let (origins, page) = pages.last_mut().expect("there's always a page");
+                origins.push((index, start));
+                page.push(part.to_string());
+            }
+            if !last {
+                pages.push((vec![], vec![]));
+            }
+            start += part.len() + 1;
+        }
+    }
+
+    pages
+}
+
+/// Aligns the lines selected by `--lines` which can fit (with `--skip-errors`), leaving the others as they are.
+pub fn align_selected(
+    lines: Vec<String>,
+    args: &Args,
+    cols_wrap: Option<(usize, bool)>,
+) -> Result<Layout, Error> {
+    let selected = select(&lines, args);
+
+    // the comments' and quotes' markers are put back after aligning their text
+    let mut lines = lines;
+    let lengths = lines.iter().map(String::len).collect::<Vec<usize>>();
+    let prefixes = match &args.comment {
+        Some(marker) => Some(strip_comment_markers(&mut lines, marker)),
+        None if args.quotes => Some(strip_quote_markers(&mut lines)),
+        None if args.common_prefix => Some(strip_common_prefix(&mut lines)),
+        None => None,
+    };
+    // the number of bytes taken off the start of each line with its marker, to follow its characters
+    let stripped = lengths
+        .iter()
+        .zip(&lines)
+        .map(|(len, line)| len - line.len())
+        .collect::<Vec<usize>>();
+    let measuring = measuring(args);
+    let prefix_width = prefixes
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|prefix| measuring.width_of(prefix))
+        .max()
+        .unwrap_or(0);
+    let cols_wrap = cols_wrap.map(|(num, wrap)| (num.saturating_sub(prefix_width), wrap));
+
+    if args.uniform_spacing {
+        for (index, line) in lines.iter_mut().enumerate() {
+            if selected.as_ref().is_none_or(|selected| selected[index]) {
+                *line = space_uniformly(line);
+            }
+        }
+    }
+
+    // the index of the input line each line starts at, several being joined into one when reflowing
+    let (lines, origins) = if args.reflow {
+        join_paragraphs(lines, &selected, &prefixes, args.uniform_spacing)
+    } else {
+        let origins = (0..lines.len()).collect();
+        (lines, origins)
+    };
+    let selected = selected.map(|selected| origins.iter().map(|&index| selected[index]).collect());
+    let prefixes = prefixes.map(|prefixes| {
+        origins
+            .iter()
+            .map(|&index| prefixes[index].clone())
+            .collect::<Vec<Option<String>>>()
+    });
+
+    // the indices of the lines which are aligned, and the lines which can't fit
+    let (lines, kept, skipped) = match (args.skip_errors, cols_wrap) {
+        (true, Some((columns, false))) => skip_overflowing(lines, args, &selected, columns),
+        _ => {
+            let kept = (0..lines.len()).collect();
+            (lines, kept, vec![])
+        }
+    };
+
+    let mut layout = match &selected {
+        None => align_lines(lines, args, cols_wrap)
+            .map_err(|e| e.map_line_index(|index| origins[kept[index]]))?,
+        Some(selected) => {
+            // each run of consecutive selected lines is aligned as its own block
+            let mut result = Layout::default();
+            let mut run = Vec::new();
+            let align_run = |run: Vec<String>, start: usize, result: &mut Layout| {
+                let aligned = align_lines(run, args, cols_wrap)
+                    .map_err(|e| e.map_line_index(|index| origins[kept[start + index]]))?;
+                result.columns = result.columns.max(aligned.columns);
+                result
+                    .lines
+                    .extend(aligned.lines.into_iter().map(|line| LineLayout {
+                        origin: start + line.origin,
+                        ..line
+                    }));
+                Ok::<(), Error>(())
+            };
+
+            let num_lines = lines.len();
+            for (index, line) in lines.into_iter().enumerate() {
+                if selected[kept[index]] {
+                    run.push(line);
+                    continue;
+                }
+
+                if !run.is_empty() {
+                    let start = index - run.len();
+                    align_run(std::mem::take(&mut run), start, &mut result)?;
+                }
+                result.lines.push(LineLayout {
+                    range: 0..line.len(),
+                    text: line,
+                    origin: index,
+                    before: 0,
+                    after: 0,
+                });
+            }
+            if !run.is_empty() {
+                let start = num_lines - run.len();
+                align_run(run, start, &mut result)?;
+            }
+            result
+        }
+    };
+
+    if args.skip_errors {
+        if !skipped.is_empty() {
+            let numbers = skipped
+                .iter()
+                .map(|(index, _)| (origins[*index] + 1).to_string())
+                .collect::<Vec<String>>();
+            eprintln!(
+                "warning: skipped the lines which can't fit: {}",
+                numbers.join(", ")
+            );
+        }
+        layout = restore_skipped(layout, &kept, skipped);
+    }
+
+    if let Some(prefixes) = prefixes {
+        for line in layout.lines.iter_mut() {
+            let Some(prefix) = &prefixes[line.origin] else {
+                continue;
+            };
+            let shift = stripped[origins[line.origin]];
+            line.range = shift + line.range.start..shift + line.range.end;
+            line.text = if line.text.is_empty() && !args.keep {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{prefix}{}{}", " ".repeat(line.before), line.text)
+            };
+            line.before = 0;
+        }
+        layout.columns += prefix_width;
+    }
+
+    for line in layout.lines.iter_mut() {
+        line.origin = origins[line.origin];
+    }
+
+    Ok(layout)
+}
+
+/// Joins the lines of each paragraph into one line, to wrap them again: the consecutive non-blank lines
+/// with the same prefix, if they're selected.
+/// Returns the joined lines, and the index of the first line of each.
+fn join_paragraphs(
+    lines: Vec<String>,
+    selected: &Option<Vec<bool>>,
+    prefixes: &Option<Vec<Option<String>>>,
+    uniform_spacing: bool,
+) -> (Vec<String>, Vec<usize>) {
+    let is_selected = |index: usize| selected.as_ref().is_none_or(|selected| selected[index]);
+    let prefix = |index: usize| {
+        prefixes
+            .as_ref()
+            .and_then(|prefixes| prefixes[index].as_ref())
+    };
+
+    let mut joined: Vec<String> = vec![];
+    let mut origins: Vec<usize> = vec![];
+    for (index, line) in lines.into_iter().enumerate() {
+        let continues = index > 0
+            && is_selected(index)
+            && is_selected(index - 1)
+            && prefix(index) == prefix(index - 1)
+            && !line.trim().is_empty()
+            && joined.last().is_some_and(|last| !last.trim().is_empty());
+
+        match joined.last_mut() {
+            Some(last) if continues => {
+                // like within the lines, a sentence ending a line is followed by two spaces
+                let last_word = last.split_whitespace().last().unwrap_or_default();
+                let space = match uniform_spacing && ends_sentence(last_word) {
+                    true => "  ",
+                    false => " ",
+                };
+                *last = format!("{}{space}{}", last.trim_end(), line.trim());
+            }
+            _ => {
+                joined.push(line);
+                origins.push(index);
+            }
+        }
+    }
+
+    (joined, origins)
+}
+
+/// Puts one space between the words of the line, and two after the sentences, like `fmt -u`: the words ending with
+/// `.`, `?`, or `!` (possibly before closing quotes or brackets) which were followed by at least two spaces or a tab.
+/// The indentation is kept.
+fn space_uniformly(line: &str) -> String {
+    let text = line.trim_start();
+    let mut spaced = line[..line.len() - text.len()].to_string();
+    let mut rest = text.trim_end();
+    while !rest.is_empty() {
+        let (word, after) = rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()));
+        spaced.push_str(word);
+        let next = after.trim_start();
+        if next.is_empty() {
+            break;
+        }
+
+        let gap = &after[..after.len() - next.len()];
+        let sentence = ends_sentence(word) && (gap.chars().count() > 1 || gap.contains('\t'));
+        spaced.push_str(if sentence { "  " } else { " " });
+        rest = next;
+    }
+    spaced
+}
+
+/// Whether the word ends a sentence, ending with `.`, `?`, or `!`, possibly before closing quotes or brackets.
+fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')', ']', '’', '”'])
+        .ends_with(['.', '?', '!'])
+}
+
+/// Removes the indentation and the comment marker (and a space after it) from the comment lines,
+/// and returns them normalized (with one space), `None` for the lines which aren't comments.
+/// With an empty marker, all the lines are comments, and only their indentation is removed.
+/// A marker ending with a space isn't followed by another one.
+fn strip_comment_markers(lines: &mut [String], marker: &str) -> Vec<Option<String>> {
+    lines
+        .iter_mut()
+        .map(|line| {
+            let content = line.trim_start();
+            let indent = &line[..line.len() - content.len()];
+            let text = content.strip_prefix(marker)?;
+
+            let prefix = match marker.is_empty() || marker.ends_with(' ') {
+                true => format!("{indent}{marker}"),
+                false => format!("{indent}{marker} "),
+            };
+            *line = text.strip_prefix(' ').unwrap_or(text).to_string();
+            Some(prefix)
+        })
+        .collect()
+}
+
+/// Removes the quote markers (`>`, possibly nested like `>>` or `> >`, and a space after them) from the quoted lines,
+/// and returns them (with one space), `None` for the lines which aren't quoted.
+fn strip_quote_markers(lines: &mut [String]) -> Vec<Option<String>> {
+    lines
+        .iter_mut()
+        .map(|line| {
+            let markers_len = line.len() - line.trim_start_matches([' ', '\t', '>']).len();
+            let end = line[..markers_len].rfind('>')? + 1;
+
+            let prefix = format!("{} ", &line[..end]);
+            let text = &line[end..];
+            *line = text.strip_prefix(' ').unwrap_or(text).to_string();
+            Some(prefix)
+        })
+        .collect()
+}
+
+/// Removes the longest start the non-blank lines have in common, up to a white-space so that words aren't cut,
+/// and returns it for them, `None` for the blank lines.
+fn strip_common_prefix(lines: &mut [String]) -> Vec<Option<String>> {
+    let mut non_blank = lines.iter().filter(|line| !line.trim().is_empty());
+    let Some(first) = non_blank.next() else {
+        return vec![None; lines.len()];
+    };
+
+    let common = non_blank.fold(first.as_str(), |common, line| {
+        let len = common
+            .char_indices()
+            .zip(line.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(common.len().min(line.len()), |((index, _), _)| index);
+        &common[..len]
+    });
+    // cut at the end of the last white-space, unless all the lines are whole words
+    let len = match common.rfind(char::is_whitespace) {
+        Some(index) => index + common[index..].chars().next().map_or(0, char::len_utf8),
+        None => 0,
+    };
+    let prefix = common[..len].to_string();
+    if prefix.is_empty() {
+        return vec![None; lines.len()];
+    }
+
+    lines
+        .iter_mut()
+        .map(|line| {
+            let text = line.strip_prefix(prefix.as_str())?;
+            *line = text.to_string();
+            Some(prefix.clone())
+        })
+        .collect()
+}
+
+/// Which lines are aligned: those selected by `--lines`, between the markers of `--between`,
+/// outside of the code blocks with `--markdown`, and only the comments with `--comment`.
+/// `None` if they all are.
+fn select(lines: &[String], args: &Args) -> Option<Vec<bool>> {
+    if args.region.lines.is_none()
+        && args.region.between.is_none()
+        && !args.markdown
+        && args.comment.is_none()
+    {
+        return None;
+    }
+
+    let mut in_code_block = false;
+    let mut in_region = false;
+    let selected = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let is_fence = args.markdown && {
+                let line = line.trim_start();
+                line.starts_with("```") || line.starts_with("~~~")
+            };
+            if is_fence {
+                in_code_block = !in_code_block;
+                return false;
+            }
+
+            if let Some([start, end]) = args.region.between.as_deref() {
+                let is_marker = match in_region {
+                    true => end.is_match(line),
+                    false => start.is_match(line),
+                };
+                if is_marker {
+                    in_region = !in_region;
+                    return false;
+                }
+                if !in_region {
+                    return false;
+                }
+            }
+
+            let is_comment = args
+                .comment
+                .as_ref()
+                .is_none_or(|marker| line.trim_start().starts_with(marker.as_str()));
+
+            !in_code_block
+                && is_comment
+                && args
+                    .region
+                    .lines
+                    .as_ref()
+                    .is_none_or(|ranges| ranges.contains(index))
+        })
+        .collect();
+
+    Some(selected)
+}
+
+/// Separates the lines which can't fit in the columns (among the selected ones),
+/// returns the other lines, their indices, and the separated lines with theirs.
+#[allow(clippy::type_complexity)]
+fn skip_overflowing(
+    lines: Vec<String>,
+    args: &Args,
+    selected: &Option<Vec<bool>>,
+    columns: usize,
+) -> (Vec<String>, Vec<usize>, Vec<(usize, String)>) {
+    let selected = |index: usize| selected.as_ref().is_none_or(|selected| selected[index]);
+    let candidates = (0..lines.len())
+        .filter(|&index| selected(index))
+        .collect::<Vec<usize>>();
+    let overflowing = overflowing(
+        candidates
+            .iter()
+            .map(|&index| lines[index].clone())
+            .collect(),
+        args,
+        columns,
+    );
+    let mut overflowing = candidates
+        .into_iter()
+        .zip(overflowing)
+        .filter_map(|(index, overflows)| overflows.then_some(index))
+        .peekable();
+
+    let mut kept = (vec![], vec![]);
+    let mut skipped = vec![];
+    for (index, line) in lines.into_iter().enumerate() {
+        if overflowing.next_if_eq(&index).is_some() {
+            skipped.push((index, line));
+        } else {
+            kept.0.push(line);
+            kept.1.push(index);
+        }
+    }
+
+    (kept.0, kept.1, skipped)
+}
+
+/// Which lines can't fit in the columns, changed and measured like [`align_lines()`] does, all at once:
+/// those which wrap when they're allowed to, or which are still too wide.
+fn overflowing(lines: Vec<String>, args: &Args, columns: usize) -> Vec<bool> {
+    let count = lines.len();
+    let Ok((lines, _)) = prepare_lines(lines, args) else {
+        // the error is shown when aligning them
+        return vec![false; count];
+    };
+    let options = Options {
+        overflow: Overflow::Wrap,
+        remainder: None,
+        last_line: None,
+        ..line_options(args, Some((columns, false)))
+    };
+    let Ok(wrapped) = layout(&lines, &options) else {
+        return vec![false; count];
+    };
+
+    let mut pieces = vec![0; count];
+    let mut overflowing = vec![false; count];
+    for line in &wrapped.lines {
+        pieces[line.origin] += 1;
+        overflowing[line.origin] |= options.width_of(&line.text) > columns;
+    }
+    overflowing
+        .into_iter()
+        .zip(pieces)
+        .map(|(too_wide, pieces)| too_wide || pieces > 1)
+        .collect()
+}
+
+/// Maps the layout's origins back to the indices of the input lines,
+/// and puts the skipped lines back in their place, as they are.
+fn restore_skipped(layout: Layout, kept: &[usize], skipped: Vec<(usize, String)>) -> Layout {
+    let mut skipped = skipped.into_iter().peekable();
+    let mut lines = Vec::with_capacity(layout.lines.len() + skipped.len());
+
+    let as_is = |(origin, text): (usize, String)| LineLayout {
+        range: 0..text.len(),
+        text,
+        origin,
+        before: 0,
+        after: 0,
+    };
+
+    for line in layout.lines {
+        let origin = kept[line.origin];
+        while let Some(skipped_line) = skipped.next_if(|(index, _)| *index < origin) {
+            lines.push(as_is(skipped_line));
+        }
+        lines.push(LineLayout { origin, ..line });
+    }
+    lines.extend(skipped.map(as_is));
+
+    Layout {
+        columns: layout.columns,
+        lines,
+    }
+}
+
+/// Fails with [`Failure::Check`] if the input isn't already aligned, printing the numbers of the offending lines.
+fn check(
+    input: &[String],
+    layout: &Layout,
+    args: &Args,
+    cols_wrap: Option<(usize, bool)>,
+) -> Result<(), Failure> {
+    let aligned = layout.render(args.keep);
+
+    let again = align_input(aligned.clone(), args, cols_wrap).map_err(Failure::Align)?;
+    if again.render(args.keep) != aligned {
+        eprintln!("warning: aligning the output again changes it, input can't pass the check with these options");
+    }
+
+    let changed = stats::changed_lines(input, &aligned, layout);
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    let mut out = stdout().lock();
+    for index in &changed {
+        writeln!(out, "line {} isn't aligned", index + 1)
+            .map_err(|e| Failure::Io(e.to_string()))?;
+    }
+    Err(Failure::Check(changed.len()))
+}
+
+/// Runs `align` with the arguments of the command line, and returns its exit code, see [`Failure`].
+pub fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("Error: {failure}");
+            failure.exit_code()
+        }
+    }
+}
+
+/// Runs `align` with the arguments of the command line.
+pub fn run() -> Result<(), Failure> {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // the options given to the subcommand, to tell which of them the defaults don't override
+    let given = matches
+        .subcommand()
+        .map_or(&matches, |(_, matches)| matches);
+    // like fmt(1), `align fmt` doesn't read the defaults
+    let is_fmt = matches!(cli.command, Some(Command::Fmt(_)));
+    let mut args = match cli.command {
+        None => cli.args,
+        Some(Command::Block(args)) => *args,
+        Some(Command::Wrap(wrap)) => wrap.into_args(),
+        Some(Command::Table(table)) => table.into_args(),
+        Some(Command::Grid(grid)) => grid.into_args(),
+        Some(Command::Check(check)) => check.into_args(),
+        Some(Command::Fmt(fmt)) => fmt.into_args(),
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(shell, &mut Cli::command(), "align", &mut std::io::stdout());
+            return Ok(());
+        }
+    };
+
+    #[cfg(feature = "tracing")]
+    if let Some(level) = args.log_level {
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            // with the time each phase took
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
+    if args.preset == Some(Preset::Help) {
+        print!("{}", Preset::list());
+        return Ok(());
+    }
+
+    if cli.generate_man {
+        return clap_mangen::Man::new(Cli::command())
+            .render(&mut std::io::stdout())
+            .map_err(|e| Failure::Io(e.to_string()));
+    }
+
+    let mut defaulted = vec![];
+    if !is_fmt {
+        let mut defaults = Defaults::from_config_files().map_err(Failure::Usage)?;
+        if let Some(path) = &args.stdin_filepath {
+            defaults = defaults.for_path(path);
+        }
+        defaulted = Defaults::from_env()
+            .map_err(Failure::Usage)?
+            .or(defaults)
+            .apply(&mut args, given)
+            .map_err(Failure::Usage)?;
+    }
+
+    args.apply_shorthands(given);
+
+    if args.stream.enabled {
+        stream::check_options(given, &defaulted)?;
+    }
+
+    if args.serve.enabled {
+        return serve::serve(&args, args.serve.socket.as_deref()).map_err(Failure::Io);
+    }
+
+    #[cfg(feature = "lsp")]
+    if args.serve.lsp {
+        return lsp::lsp(&args).map_err(Failure::Io);
+    }
+
+    let terminal_width = get_needed_terminal_width(&args).map_err(Failure::Usage)?;
+    let cols_wrap = get_cols_wrap(&args, terminal_width);
+
+    if let Some(path) = &args.watch {
+        return watch::watch(path, || {
+            // the terminal may have been resized
+            let cols_wrap = get_cols_wrap(&args, get_needed_terminal_width(&args)?);
+            let lines = normalize_lines(read_text(path, args.strict)?, &args)?;
+            // to tell where the lines start in JSON
+            let input = (args.output_format == OutputFormat::Json).then(|| lines.clone());
+            let mut layout = align_input(lines, &args, cols_wrap)?;
+            if args.middle {
+                place_middle(&mut layout, &args)?;
+            }
+
+            let style = Style {
+                keep_spaces: args.keep,
+                show_spaces: args.show_spaces,
+                fill: args.fill,
+                pad_color: args.pad_color.clone(),
+            };
+            output::render(&layout, &args.output_format, &style, input.as_deref())
+        })
+        .map_err(Failure::Io);
+    }
+
+    if args.stream.enabled {
+        return stream::stream(&args, cols_wrap);
+    }
+
+    let files = args
+        .files
+        .iter()
+        .map(|path| read_text(path, args.strict))
+        .collect::<Result<Vec<Vec<String>>, String>>()
+        .map_err(Failure::Io)?
+        .into_iter()
+        .zip(&args.files)
+        .map(|(lines, path)| {
+            normalize_lines(lines, &args).map_err(|e| format!("{}: {e}", path.display()))
+        })
+        .collect::<Result<Vec<Vec<String>>, String>>()
+        .map_err(Failure::Align)?;
+
+    #[cfg(feature = "clipboard")]
+    let lines = if !files.is_empty() {
+        files.concat()
+    } else if args.clipboard.paste {
+        clipboard::paste().map_err(Failure::Io)?
+    } else {
+        get_text(
+            &mut Progress::new("read", args.progress, None),
+            args.strict,
+            args.gzip,
+        )
+        .map_err(Failure::Io)?
+    };
+    #[cfg(not(feature = "clipboard"))]
+    let lines = if !files.is_empty() {
+        files.concat()
+    } else {
+        get_text(
+            &mut Progress::new("read", args.progress, None),
+            args.strict,
+            args.gzip,
+        )
+        .map_err(Failure::Io)?
+    };
+
+    let lines = normalize_lines(lines, &args).map_err(Failure::Align)?;
+
+    if args.verbose {
+        if files.is_empty() {
+            warn_about(&lines, &args, None);
+        }
+        for (lines, path) in files.iter().zip(&args.files) {
+            warn_about(lines, &args, Some(path));
+        }
+    }
+
+    if args.debug_width {
+        debug_widths(&lines, &measuring(&args));
+    }
+
+    if args.measure {
+        print_measure(&lines, args.trim, &measuring(&args));
+        return Ok(());
+    }
+
+    if args.interactive {
+        if let Some(aligned) = interactive::interactive(&lines, &mut args).map_err(Failure::Io)? {
+            let mut out = BufWriter::new(stdout().lock());
+            for line in aligned {
+                writeln!(out, "{line}").map_err(|e| Failure::Io(e.to_string()))?;
+            }
+            out.flush().map_err(|e| Failure::Io(e.to_string()))?;
+        }
+        return Ok(());
+    }
+
+    let input = if args.diff
+        || args.stats
+        || args.check
+        || args.only_changed
+        || args.offsets_json.is_some()
+        || args.output_format == OutputFormat::Json
+    {
+        lines.clone()
+    } else {
+        vec![]
+    };
+
+    let mut layout = match (files.is_empty(), args.modeline) {
+        (true, false) => align_input(lines, &args, cols_wrap),
+        (true, true) => align_with_modeline(&lines, &args, given, terminal_width, cols_wrap),
+        (false, false) => align_files(&files, &args, cols_wrap, |lines, cols_wrap| {
+            align_input(lines.to_vec(), &args, cols_wrap)
+        }),
+        (false, true) => align_files(&files, &args, cols_wrap, |lines, cols_wrap| {
+            align_with_modeline(lines, &args, given, terminal_width, cols_wrap)
+        }),
+    }
+    .map_err(Failure::Align)?;
+
+    if args.only_changed {
+        keep_unchanged(&input, &mut layout, args.keep);
+    }
+
+    if args.check {
+        return check(&input, &layout, &args, cols_wrap);
+    }
+
+    if args.middle {
+        place_middle(&mut layout, &args).map_err(Failure::Usage)?;
+    }
+
+    if let Some(rows) = args.pagination.paginate {
+        let mut start = 0;
+        let mut names = vec![];
+        for (lines, path) in files.iter().zip(&args.files) {
+            names.push((start, path.display().to_string()));
+            start += lines.len();
+        }
+        if let (true, Some(path)) = (files.is_empty(), &args.stdin_filepath) {
+            names.push((0, path.display().to_string()));
+        }
+        paginate::paginate(
+            &mut layout,
+            rows,
+            args.pagination.header.as_deref(),
+            args.pagination.footer.as_deref(),
+            &names,
+            args.bias,
+            &measuring(&args),
+        )
+        .map_err(Failure::Usage)?;
+    }
+
+    if let Some(path) = &args.offsets_json {
+        output::write_offsets(path, &input, &layout, args.keep).map_err(Failure::Io)?;
+    }
+
+    if args.stats {
+        let mut stats = Stats::new(
+            &input,
+            &layout.render(args.keep),
+            &layout,
+            args.trim,
+            &measuring(&args),
+        );
+        stats.terminal_width = terminal_width;
+        eprintln!("{stats}");
+    }
+
+    let style = Style {
+        keep_spaces: args.keep,
+        show_spaces: args.show_spaces,
+        fill: args.fill,
+        pad_color: args.pad_color.clone(),
+    };
+    let json = args.output_format == OutputFormat::Json;
+    let mut aligned = output::render(
+        &layout,
+        &args.output_format,
+        &style,
+        json.then_some(input.as_slice()),
+    )
+    .map_err(Failure::Io)?;
+    if args.diff {
+        aligned = output::diff(&input, &aligned);
+    }
+
+    #[cfg(feature = "clipboard")]
+    if args.clipboard.copy {
+        return clipboard::copy(&aligned).map_err(Failure::Io);
+    }
+
+    let total = aligned.lines().count();
+    if args.pager.is_needed(total) {
+        return pager::page(&aligned).map_err(Failure::Io);
+    }
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("write", lines = total).entered();
+    let mut progress = Progress::new("written", args.progress, Some(total));
+    let mut out = BufWriter::new(stdout().lock());
+    for line in aligned.split_inclusive('\n') {
+        out.write_all(line.as_bytes())
+            .map_err(|e| Failure::Io(e.to_string()))?;
+        progress.tick(line.len());
+    }
+    out.flush().map_err(|e| Failure::Io(e.to_string()))?;
+    progress.finish();
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+};
+use lsp_types::request::{Formatting, RangeFormatting, Request as _};
+use lsp_types::{
+    DocumentFormattingParams, DocumentRangeFormattingParams, FormattingOptions, FormattingProperty,
+    OneOf, Position, Range, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextEdit, Url,
+};
+
+use crate::{align_input, get_cols_wrap, normalize_lines, Args};
+
+/// Runs a language server on stdin and stdout, which formats documents (or ranges of their lines) by aligning them,
+/// until the client shuts it down.
+///
+/// The formatting options of the requests override the server's:
+/// `trimTrailingWhitespace` removes the spaces on the right of the lines (or keeps them if it's false),
+/// and the properties `align` (`left`, `center`, or `right`), `columns`, and `wrap` are the options of the same names.
+pub fn lsp(args: &Args) -> Result<(), String> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    let capabilities = serde_json::to_value(capabilities).map_err(|e| e.to_string())?;
+    connection
+        .initialize(capabilities)
+        .map_err(|e| e.to_string())?;
+
+    let mut documents = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection
+                    .handle_shutdown(&request)
+                    .map_err(|e| e.to_string())?
+                {
+                    break;
+                }
+                let response = respond(args, &documents, request);
+                connection
+                    .sender
+                    .send(Message::Response(response))
+                    .map_err(|e| e.to_string())?;
+            }
+            Message::Notification(notification) => track(&mut documents, notification),
+            Message::Response(_) => {}
+        }
+    }
+
+    // the writing thread stops once the connection is gone
+    drop(connection);
+    io_threads.join().map_err(|e| e.to_string())
+}
+
+/// Keeps the text of the open documents up to date.
+fn track(documents: &mut HashMap<Url, String>, notification: Notification) {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            if let Ok(params) = notification
+                .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+            {
+                documents.insert(params.text_document.uri, params.text_document.text);
+            }
+        }
+        DidChangeTextDocument::METHOD => {
+            if let Ok(params) = notification
+                .extract::<lsp_types::DidChangeTextDocumentParams>(DidChangeTextDocument::METHOD)
+            {
+                // the whole text is sent each time
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    documents.insert(params.text_document.uri, change.text);
+                }
+            }
+        }
+        DidCloseTextDocument::METHOD => {
+            if let Ok(params) = notification
+                .extract::<lsp_types::DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+            {
+                documents.remove(&params.text_document.uri);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Answers a formatting request with the edit aligning the document, or the range of its lines.
+fn respond(args: &Args, documents: &HashMap<Url, String>, request: Request) -> Response {
+    let id = request.id.clone();
+    let (uri, options, lines) = match request.method.as_str() {
+        Formatting::METHOD => match request.extract::<DocumentFormattingParams>(Formatting::METHOD)
+        {
+            Ok((_, params)) => (params.text_document.uri, params.options, None),
+            Err(e) => {
+                return Response::new_err(id, ErrorCode::InvalidParams as i32, format!("{e:?}"))
+            }
+        },
+        RangeFormatting::METHOD => {
+            match request.extract::<DocumentRangeFormattingParams>(RangeFormatting::METHOD) {
+                Ok((_, params)) => {
+                    let Range { start, end } = params.range;
+                    // a range ending at the start of a line doesn't include it
+                    let last = if end.character == 0 && end.line > start.line {
+                        end.line - 1
+                    } else {
+                        end.line
+                    };
+                    (
+                        params.text_document.uri,
+                        params.options,
+                        Some(start.line..last + 1),
+                    )
+                }
+                Err(e) => {
+                    return Response::new_err(id, ErrorCode::InvalidParams as i32, format!("{e:?}"))
+                }
+            }
+        }
+        method => {
+            return Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request {method}"),
+            )
+        }
+    };
+
+    let Some(text) = documents.get(&uri) else {
+        return Response::new_err(
+            id,
+            ErrorCode::InvalidParams as i32,
+            format!("unknown document {uri}"),
+        );
+    };
+    match format(args, &options, text, lines) {
+        Ok(edits) => Response::new_ok(id, edits),
+        // the client shows the message
+        Err(e) => Response::new_err(id, ErrorCode::RequestFailed as i32, e),
+    }
+}
+
+/// The edit replacing the `lines` of the text (all of them if `None`) by their aligned version.
+fn format(
+    args: &Args,
+    options: &FormattingOptions,
+    text: &str,
+    lines: Option<std::ops::Range<u32>>,
+) -> Result<Vec<TextEdit>, String> {
+    let all = text.lines().collect::<Vec<&str>>();
+    let lines = lines.unwrap_or(0..all.len() as u32);
+    let start = (lines.start as usize).min(all.len());
+    let end = (lines.end as usize).min(all.len());
+    if start == end {
+        return Ok(vec![]);
+    }
+
+    let args = with_options(args, options)?;
+    let selected = all[start..end]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    let selected = normalize_lines(selected, &args)?;
+    // there's no terminal to take the width of
+    let layout = align_input(selected, &args, get_cols_wrap(&args, None))?;
+    let aligned = layout.into_rendered(args.keep).join("\n");
+
+    // the last line ends at its last character, counted in UTF-16 units
+    let last = all[end - 1].encode_utf16().count() as u32;
+    Ok(vec![TextEdit {
+        range: Range::new(
+            Position::new(start as u32, 0),
+            Position::new(end as u32 - 1, last),
+        ),
+        new_text: aligned,
+    }])
+}
+
+/// The server's args, overridden by the formatting options of a request.
+fn with_options(args: &Args, options: &FormattingOptions) -> Result<Args, String> {
+    let mut args = args.clone();
+    if let Some(trim) = options.trim_trailing_whitespace {
+        args.keep = !trim;
+    }
+
+    for (name, value) in &options.properties {
+        match (name.as_str(), value) {
+            ("align", FormattingProperty::String(align)) => {
+                let align = align.parse::<align_text::Where>()?;
+                args.outer = align.clone();
+                args.inner = align;
+            }
+            ("columns", FormattingProperty::Number(columns)) => {
+                args.columns = Some(usize::try_from(*columns).map_err(|e| e.to_string())?);
+            }
+            ("wrap", FormattingProperty::Bool(wrap)) => {
+                args.wrap = wrap.then_some(crate::WrapMode::Anywhere);
+            }
+            // the editors' own properties
+            _ => {}
+        }
+    }
+
+    Ok(args)
+}
@@ -0,0 +1,245 @@
+use std::path::Path;
+
+use align_text::{Layout, LineLayout};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::color::{PadColor, RESET};
+
+/// How the aligned text is written out.
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The aligned lines.
+    #[default]
+    Text,
+    /// A JSON object with the aligned lines and how they were placed.
+    Json,
+    /// HTML-escaped lines in a <pre> element.
+    Html,
+    /// HTML-escaped lines with &nbsp; for spaces and <br> between lines, for places where <pre> isn't kept.
+    HtmlInline,
+    /// Each line as an escaped JSON string literal, which most languages read too (e.g. JavaScript, Python).
+    Literals,
+    /// A single escaped string literal with \n between the lines.
+    Literal,
+}
+
+#[derive(Serialize)]
+struct JsonOutput {
+    /// The final number of columns.
+    columns: usize,
+    lines: Vec<JsonLine>,
+}
+
+#[derive(Serialize)]
+struct JsonLine {
+    text: String,
+    /// The index of the input line it comes from.
+    origin: usize,
+    /// The position (in characters) in the input line of its first character, when the input is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+    /// Number of spaces inserted before the content.
+    before: usize,
+    /// Number of spaces inserted after the content.
+    after: usize,
+}
+
+#[derive(Serialize)]
+struct JsonOffsets {
+    /// The index of the input line.
+    line: usize,
+    /// Number of spaces inserted before it.
+    before: usize,
+    /// Number of spaces inserted after it.
+    after: usize,
+    /// The output line and position of each of its characters, `null` for those removed.
+    positions: Vec<Option<(usize, usize)>>,
+}
+
+/// How the lines are built from the layout.
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    /// Whether to keep the spaces on the right.
+    pub keep_spaces: bool,
+    /// Whether to show the inserted spaces as `·`.
+    pub show_spaces: bool,
+    /// The character to pad the lines with instead of spaces.
+    pub fill: Option<char>,
+    /// The colors of the lines, padding included (which is then kept on the right), to make a solid block.
+    pub pad_color: Option<PadColor>,
+}
+
+impl Style {
+    fn line(&self, line: &LineLayout) -> String {
+        let padding = match (self.show_spaces, self.fill) {
+            (true, _) => '·',
+            (false, Some(fill)) => fill,
+            (false, None) => ' ',
+        }
+        .to_string();
+        let after = if self.keep_spaces || self.pad_color.is_some() {
+            line.after
+        } else {
+            0
+        };
+
+        let Some(color) = &self.pad_color else {
+            return format!(
+                "{}{}{}",
+                padding.repeat(line.before),
+                line.text,
+                padding.repeat(after)
+            );
+        };
+
+        // the text's own resets would end the colors early
+        let text = line
+            .text
+            .replace("\x1b[0m", &format!("{RESET}{}", color.sgr()))
+            .replace("\x1b[m", &format!("{RESET}{}", color.sgr()));
+        format!(
+            "{}{}{text}{}{RESET}",
+            color.sgr(),
+            padding.repeat(line.before),
+            padding.repeat(after)
+        )
+    }
+
+    fn lines(&self, layout: &Layout) -> Vec<String> {
+        layout.lines.iter().map(|line| self.line(line)).collect()
+    }
+}
+
+/// Writes the aligned text in the given format.
+/// `input`: The lines which were aligned, to tell where each output line starts in them in JSON.
+pub fn render(
+    layout: &Layout,
+    format: &OutputFormat,
+    style: &Style,
+    input: Option<&[String]>,
+) -> Result<String, String> {
+    match format {
+        OutputFormat::Text => Ok(style
+            .lines(layout)
+            .into_iter()
+            .map(|line| line + "\n")
+            .collect()),
+        OutputFormat::Json => {
+            let origins = input.map(|input| layout.origins(input));
+            let output = JsonOutput {
+                columns: layout.columns,
+                lines: layout
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .map(|(index, line)| JsonLine {
+                        text: style.line(line),
+                        origin: line.origin,
+                        offset: origins.as_ref().map(|origins| origins[index].1),
+                        before: line.before,
+                        after: if style.keep_spaces { line.after } else { 0 },
+                    })
+                    .collect(),
+            };
+
+            serde_json::to_string_pretty(&output)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string())
+        }
+        OutputFormat::Html => {
+            let lines = style
+                .lines(layout)
+                .iter()
+                .map(|line| escape_html(line))
+                .collect::<Vec<String>>();
+
+            Ok(format!("<pre>\n{}\n</pre>\n", lines.join("\n")))
+        }
+        OutputFormat::HtmlInline => {
+            let lines = style
+                .lines(layout)
+                .iter()
+                .map(|line| escape_html(line).replace(' ', "&nbsp;"))
+                .collect::<Vec<String>>();
+
+            Ok(lines.join("<br>\n") + "\n")
+        }
+        OutputFormat::Literals => Ok(style
+            .lines(layout)
+            .iter()
+            .map(|line| format!("\"{}\"\n", escape_literal(line)))
+            .collect()),
+        OutputFormat::Literal => {
+            let text = style.lines(layout).join("\n");
+            Ok(format!("\"{}\"\n", escape_literal(&text)))
+        }
+    }
+}
+
+/// Writes where each input line ended up to `path`, as JSON.
+pub fn write_offsets(
+    path: &Path,
+    input: &[String],
+    layout: &Layout,
+    keep_spaces: bool,
+) -> Result<(), String> {
+    let offsets = layout
+        .offsets(input)
+        .into_iter()
+        .map(|offsets| JsonOffsets {
+            line: offsets.line_index,
+            before: offsets.before,
+            after: if keep_spaces { offsets.after } else { 0 },
+            positions: offsets.positions,
+        })
+        .collect::<Vec<JsonOffsets>>();
+
+    let json = serde_json::to_string(&offsets).map_err(|e| e.to_string())?;
+    std::fs::write(path, json + "\n").map_err(|e| format!("couldn't write {}: {e}", path.display()))
+}
+
+/// Escapes the text for a JSON string literal, the other control characters (e.g. ESC) as `\u001b`.
+fn escape_literal(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// A unified diff from the input lines to the aligned text.
+pub fn diff(input: &[String], aligned: &str) -> String {
+    let input = input
+        .iter()
+        .map(|line| line.clone() + "\n")
+        .collect::<String>();
+
+    similar::TextDiff::from_lines(input.as_str(), aligned)
+        .unified_diff()
+        .header("input", "aligned")
+        .to_string()
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,63 @@
+use std::io::{ErrorKind, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use clap::ValueEnum;
+
+/// The pager used when `$PAGER` isn't set, chopping the long lines instead of wrapping them.
+const DEFAULT_PAGER: &str = "less -S";
+
+/// When to show the output in a pager.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Paging {
+    /// When stdout is a terminal, and the output is taller than it.
+    Auto,
+    Always,
+    #[default]
+    Never,
+}
+
+impl Paging {
+    /// Whether `lines` lines of output should be paged.
+    pub fn is_needed(&self, lines: usize) -> bool {
+        match self {
+            Paging::Always => true,
+            Paging::Never => false,
+            Paging::Auto => {
+                std::io::stdout().is_terminal()
+                    && term_size::dimensions_stdout().is_some_and(|(_, height)| lines > height)
+            }
+        }
+    }
+}
+
+/// Writes the text to `$PAGER` (or `less -S`) and waits for it to be closed.
+/// Like git, `less` is given the `FRS` options if `$LESS` isn't set, so that the lines are chopped instead of wrapped.
+pub fn page(text: &str) -> Result<(), String> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or(DEFAULT_PAGER.to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or("less");
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRS");
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("couldn't start the pager '{pager}': {e}"))?;
+
+    if let Some(mut input) = child.stdin.take() {
+        // the pager may be quit before reading everything
+        match input.write_all(text.as_bytes()) {
+            Err(e) if e.kind() != ErrorKind::BrokenPipe => return Err(e.to_string()),
+            _ => {}
+        }
+    }
+
+    child.wait().map_err(|e| e.to_string())?;
+    Ok(())
+}
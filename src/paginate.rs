@@ -0,0 +1,148 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use align_text::{Bias, Layout, LineAligner, LineLayout, Options, Where};
+
+/// The options of the pages: those of the input with --pages, and of the output with --paginate.
+#[derive(clap::Args, Debug, Clone)]
+pub struct PageArgs {
+    /// Align each page (separated by form feeds) on its own, the form feeds being written on lines of their own.
+    #[arg(long, action, conflicts_with_all = ["lines", "between", "flow"])]
+    pub pages: bool,
+
+    /// Pad each page with blank lines up to ROWS lines, with --pages.
+    #[arg(long, value_name = "ROWS", requires = "pages")]
+    pub page_height: Option<usize>,
+
+    /// Split the aligned text into pages of ROWS lines, like pr(1), the last one padded with blank lines.
+    #[arg(
+        long,
+        value_name = "ROWS",
+        conflicts_with_all = ["pages", "middle", "interactive", "check", "diff"]
+    )]
+    pub paginate: Option<usize>,
+
+    /// Start each page with TEMPLATE, centered, with --paginate. '{file}', '{date}', '{page}', and '{pages}'
+    /// are replaced by the name of the file, the date (in UTC), the page's number, and the number of pages of the file.
+    #[arg(long, value_name = "TEMPLATE", requires = "paginate")]
+    pub header: Option<String>,
+
+    /// End each page with TEMPLATE, centered, with --paginate (e.g. 'Page {page}/{pages}').
+    #[arg(long, value_name = "TEMPLATE", requires = "paginate")]
+    pub footer: Option<String>,
+}
+
+/// Splits the aligned lines into pages of `rows` lines, like pr(1): each one starts with the header and ends with
+/// the footer (centered within the columns, and separated from the text by a blank line), and the last one is
+/// padded with blank lines. The header and footer can have the placeholders `{file}`, `{date}`, `{page}`, and `{pages}`,
+/// `files` being the index of the first input line of each file and its name. The header and footer are measured with `measuring`.
+/// Each file starts on a new page, so that `{file}` names the file of all the lines of the page,
+/// and `{page}` and `{pages}` count the pages of the file.
+pub fn paginate(
+    layout: &mut Layout,
+    rows: usize,
+    header: Option<&str>,
+    footer: Option<&str>,
+    files: &[(usize, String)],
+    bias: Bias,
+    measuring: &Options,
+) -> Result<(), String> {
+    let margin_rows = header.iter().chain(&footer).count() * 2;
+    let body_rows = rows
+        .checked_sub(margin_rows)
+        .filter(|&body_rows| body_rows > 0)
+        .ok_or(format!(
+            "pages of {rows} rows leave no room for the text between the header and the footer"
+        ))?;
+
+    let date = now();
+    let columns = layout.columns;
+
+    // each file starts on a new page, and its pages are numbered on their own
+    let file_of = |origin: usize| files.iter().rposition(|(start, _)| *start <= origin);
+    let mut parts: Vec<(Option<usize>, Vec<LineLayout>)> = vec![];
+    for line in std::mem::take(&mut layout.lines) {
+        let file = file_of(line.origin);
+        match parts.last_mut() {
+            Some((last, lines)) if *last == file => lines.push(line),
+            _ => parts.push((file, vec![line])),
+        }
+    }
+    if parts.is_empty() {
+        parts.push((file_of(0), vec![]));
+    }
+
+    for (file, lines) in parts {
+        let file = file.map_or("", |index| files[index].1.as_str());
+        let first = lines.first().map_or(0, |line| line.origin);
+        let pages = lines.len().div_ceil(body_rows).max(1);
+
+        let mut chunks = lines.into_iter();
+        for page in 1..=pages {
+            let body = chunks.by_ref().take(body_rows).collect::<Vec<LineLayout>>();
+            let origin = body.first().map_or(first, |line| line.origin);
+            let fill = |template: &str| {
+                template
+                    .replace("{file}", file)
+                    .replace("{date}", &date)
+                    .replace("{page}", &page.to_string())
+                    .replace("{pages}", &pages.to_string())
+            };
+            let centered = |text: String| {
+                let space = columns.saturating_sub(measuring.width_of(&text));
+                let before = Where::Center.spaces_before(&text, origin, space, bias);
+                LineLayout {
+                    text,
+                    origin,
+                    range: 0..0,
+                    before,
+                    after: space - before,
+                }
+            };
+            let blank = || LineLayout {
+                text: String::new(),
+                origin,
+                range: 0..0,
+                before: 0,
+                after: columns,
+            };
+
+            let padding = body_rows - body.len();
+            if let Some(header) = header {
+                layout.lines.extend([centered(fill(header)), blank()]);
+            }
+            layout.lines.extend(body);
+            layout.lines.extend((0..padding).map(|_| blank()));
+            if let Some(footer) = footer {
+                layout.lines.extend([blank(), centered(fill(footer))]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The current date and time in UTC, like `2024-03-09 14:05`.
+fn now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+
+    // from the days since 1970-01-01 to the date, in eras of 400 years starting on March 1st
+    let days = days as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
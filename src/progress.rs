@@ -0,0 +1,78 @@
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// How often the progress is updated.
+const INTERVAL: Duration = Duration::from_millis(200);
+
+/// Reports how many lines and bytes were processed on stderr, at most every [`INTERVAL`].
+pub struct Progress {
+    label: &'static str,
+    enabled: bool,
+    total: Option<usize>,
+    lines: usize,
+    bytes: usize,
+    last: Instant,
+}
+
+impl Progress {
+    /// `total`: The number of lines expected, if known.
+    pub fn new(label: &'static str, enabled: bool, total: Option<usize>) -> Progress {
+        Progress {
+            label,
+            enabled,
+            total,
+            lines: 0,
+            bytes: 0,
+            last: Instant::now(),
+        }
+    }
+
+    /// Counts a line of `bytes` bytes.
+    pub fn tick(&mut self, bytes: usize) {
+        self.lines += 1;
+        self.bytes += bytes;
+
+        if self.enabled && self.last.elapsed() >= INTERVAL {
+            self.last = Instant::now();
+            self.report();
+        }
+    }
+
+    /// Reports the final count, and ends the progress line.
+    pub fn finish(&mut self) {
+        if self.enabled {
+            self.report();
+            eprintln!();
+        }
+    }
+
+    fn report(&self) {
+        let lines = match self.total {
+            Some(total) => format!("{}/{total}", self.lines),
+            None => self.lines.to_string(),
+        };
+        eprint!(
+            "\r{}: {lines} lines, {}",
+            self.label,
+            human_bytes(self.bytes)
+        );
+        let _ = std::io::stderr().flush();
+    }
+}
+
+fn human_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
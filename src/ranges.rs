@@ -0,0 +1,81 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use regex::Regex;
+
+/// The options selecting the lines to align, the others being left untouched.
+#[derive(clap::Args, Debug, Clone)]
+pub struct RegionArgs {
+    /// Only align these lines (e.g. 5-20,30-), others are left untouched.
+    #[arg(long, value_name = "RANGES")]
+    pub lines: Option<LineRanges>,
+
+    /// Only align the lines between a line matching START and the next one matching END (e.g. '<!-- align:on -->'
+    /// and '<!-- align:off -->'), each region on its own, the others and the markers are left untouched.
+    /// A region without an END goes on to the end of the text.
+    #[arg(long, num_args = 2, value_names = ["START", "END"], value_parser = crate::parse_regex)]
+    pub between: Option<Vec<Regex>>,
+}
+
+/// A set of 1-based, inclusive line ranges, parsed from a spec like `5-20,30-`.
+///
+/// Each comma-separated item can be:
+/// * `N`: a single line.
+/// * `N-M`: lines `N` through `M`.
+/// * `N-`: line `N` through the end of the text.
+/// * `-M`: the first line through line `M`.
+#[derive(Debug, Clone)]
+pub struct LineRanges(Vec<RangeInclusive<usize>>);
+
+impl LineRanges {
+    /// Whether the line at `index` (0-based) is selected.
+    pub fn contains(&self, index: usize) -> bool {
+        let number = index + 1;
+        self.0.iter().any(|range| range.contains(&number))
+    }
+}
+
+impl FromStr for LineRanges {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let parse_bound = |bound: &str| -> Result<usize, String> {
+            match bound.trim().parse::<usize>() {
+                Ok(0) => Err("line numbers start at 1".to_string()),
+                Ok(n) => Ok(n),
+                Err(_) => Err(format!("invalid line number '{bound}'")),
+            }
+        };
+
+        let ranges = spec
+            .split(',')
+            .map(|item| {
+                let range = match item.split_once('-') {
+                    None => {
+                        let line = parse_bound(item)?;
+                        line..=line
+                    }
+                    Some((start, end)) => {
+                        let start = match start.trim() {
+                            "" => 1,
+                            start => parse_bound(start)?,
+                        };
+                        let end = match end.trim() {
+                            "" => usize::MAX,
+                            end => parse_bound(end)?,
+                        };
+                        start..=end
+                    }
+                };
+
+                if range.is_empty() {
+                    return Err(format!("empty line range '{item}'"));
+                }
+
+                Ok(range)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(LineRanges(ranges))
+    }
+}
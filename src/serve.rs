@@ -0,0 +1,270 @@
+use std::io::{self, stdin, stdout, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{parser::ValueSource, ArgMatches, CommandFactory};
+
+use crate::{align_input, get_cols_wrap, normalize_lines, Args};
+
+/// The length of the longest text a request can have, the longer ones being skipped and answered with an error.
+pub const MAX_LENGTH: usize = 16 << 20;
+
+/// The options which a request can't have: those reading or writing elsewhere than the request and its response,
+/// and those which only apply to the output of the command (the response is the aligned text as it is).
+const UNSUPPORTED: [&str; 32] = [
+    "files",
+    "serve",
+    "lsp",
+    "socket",
+    "watch",
+    "stream",
+    "jobs",
+    "gzip",
+    "progress",
+    "paste",
+    "copy",
+    "verbose",
+    "debug_width",
+    "measure",
+    "interactive",
+    "diff",
+    "stats",
+    "check",
+    "only_changed",
+    "middle",
+    "paginate",
+    "header",
+    "footer",
+    "offsets_json",
+    "output_format",
+    "show_spaces",
+    "fill",
+    "pad_color",
+    "pager",
+    "modeline",
+    "stdin_filepath",
+    "log_level",
+];
+
+/// The options of the servers: --serve, and --lsp.
+#[derive(clap::Args, Debug, Clone)]
+pub struct ServeArgs {
+    /// Align texts on demand, sent with a length-prefixed protocol on stdin (or --socket).
+    /// Without --columns, each text's own width is used.
+    #[arg(
+        id = "serve",
+        long = "serve",
+        action,
+        conflicts_with_all = ["watch", "interactive", "diff", "check", "stats", "progress", "middle"]
+    )]
+    pub enabled: bool,
+
+    /// Run a language server on stdin and stdout, formatting documents and ranges of lines by aligning them.
+    #[cfg(feature = "lsp")]
+    #[arg(
+        long,
+        action,
+        conflicts_with_all = ["serve", "watch", "interactive", "diff", "check", "stats", "progress", "middle"]
+    )]
+    pub lsp: bool,
+
+    /// The unix socket to listen on with --serve, replacing a stale one and removed when the server stops.
+    #[arg(long, value_name = "PATH", requires = "serve")]
+    pub socket: Option<PathBuf>,
+}
+
+/// Aligns texts on demand, until the end of the input.
+///
+/// Each request is a header line with the length of the text in bytes, optionally followed by options
+/// (as they'd be written on the command line, instead of the server's), then the text itself: `12 -a center -c 20\n<text>`.
+/// The options are split into words like a shell would, see [`split_words()`], and some aren't supported, see [`UNSUPPORTED`].
+/// Each response is a header line with the length of the aligned text, then the aligned text,
+/// or the header `error <length>` followed by the error message.
+///
+/// Without a socket, requests are read from stdin and answered on stdout.
+/// With one, it's created (replacing a stale one) and the requests of each connection are answered in turn,
+/// until the server is interrupted, which removes it.
+pub fn serve(args: &Args, socket: Option<&Path>) -> Result<(), String> {
+    match socket {
+        None => handle(args, stdin().lock(), stdout().lock()),
+        Some(path) => listen(args, path),
+    }
+}
+
+/// The socket's file, removed when it's dropped.
+#[cfg(unix)]
+struct Socket<'a> {
+    path: &'a Path,
+}
+
+#[cfg(unix)]
+impl Drop for Socket<'_> {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.path);
+    }
+}
+
+#[cfg(unix)]
+fn listen(args: &Args, path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    use crate::remove_on_signal;
+
+    // left behind by a server which didn't stop cleanly, other files aren't replaced
+    if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        std::fs::remove_file(path)
+            .map_err(|e| format!("couldn't remove the stale socket {}: {e}", path.display()))?;
+    }
+    let listener = UnixListener::bind(path)
+        .map_err(|e| format!("couldn't listen on {}: {e}", path.display()))?;
+    let socket = Socket { path };
+
+    remove_on_signal(path)?;
+
+    for stream in listener.incoming() {
+        let stream = stream.map_err(|e| e.to_string())?;
+        let reader = stream.try_clone().map_err(|e| e.to_string())?;
+        // a client's error doesn't stop the server
+        if let Err(e) = handle(args, BufReader::new(reader), stream) {
+            eprintln!("{e}");
+        }
+    }
+
+    drop(socket);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn listen(_args: &Args, _path: &Path) -> Result<(), String> {
+    Err("sockets are only supported on unix".to_string())
+}
+
+/// Answers the requests read from `input` until its end.
+fn handle(args: &Args, mut input: impl BufRead, mut output: impl Write) -> Result<(), String> {
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header).map_err(|e| e.to_string())? == 0 {
+            return Ok(());
+        }
+
+        let header = header.trim();
+        let (length, options) = header
+            .split_once(char::is_whitespace)
+            .unwrap_or((header, ""));
+        let length = length
+            .parse::<usize>()
+            .map_err(|_| format!("invalid request header '{header}'"))?;
+
+        // the text is read as it comes, not allocated upfront
+        let mut text = vec![];
+        let mut body = input.by_ref().take(length as u64);
+        let response = if length > MAX_LENGTH {
+            io::copy(&mut body, &mut io::sink()).map_err(|e| e.to_string())?;
+            Err(format!(
+                "the text is {length} bytes long, longer than the {MAX_LENGTH} bytes allowed"
+            ))
+        } else {
+            body.read_to_end(&mut text).map_err(|e| e.to_string())?;
+            if text.len() < length {
+                return Err(format!(
+                    "the input ended after {} of the {length} bytes of the text",
+                    text.len()
+                ));
+            }
+            String::from_utf8(text)
+                .map_err(|_| "the text isn't valid UTF-8".to_string())
+                .and_then(|text| align(args, &split_words(options)?, &text))
+        };
+
+        match response {
+            Ok(aligned) => write!(output, "{}\n{aligned}", aligned.len()),
+            Err(e) => write!(output, "error {}\n{e}", e.len()),
+        }
+        .and_then(|_| output.flush())
+        .map_err(|e| e.to_string())?;
+    }
+}
+
+/// Splits the options of a request into words like a shell: at the white-spaces, except for those quoted
+/// with `'` or `"`, or escaped with `\`, e.g. `--header 'Page {page}'`.
+pub(crate) fn split_words(options: &str) -> Result<Vec<String>, String> {
+    let unterminated = || format!("unterminated quote or escape in the options '{options}'");
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+
+    let mut chars = options.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' | '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        quote if quote == ch => break,
+                        // only double quotes have escapes
+                        '\\' if ch == '"' => word.push(chars.next().ok_or_else(unterminated)?),
+                        inner => word.push(inner),
+                    }
+                }
+            }
+            '\\' => word
+                .get_or_insert_with(String::new)
+                .push(chars.next().ok_or_else(unterminated)?),
+            ch if ch.is_whitespace() => words.extend(word.take()),
+            ch => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+/// Aligns the text of a request, with its own options if it has some.
+fn align(args: &Args, options: &[String], text: &str) -> Result<String, String> {
+    let request_args;
+    let args = if options.is_empty() {
+        args
+    } else {
+        let matches = Args::command()
+            .no_binary_name(true)
+            .try_get_matches_from(options)
+            .map_err(|e| e.to_string())?;
+        check_options(&matches)?;
+        request_args = Args::from_matches(&matches).map_err(|e| e.to_string())?;
+        &request_args
+    };
+
+    let lines = normalize_lines(text.lines().map(|line| line.to_string()).collect(), args)?;
+    // there's no terminal to take the width of
+    let layout = align_input(lines, args, get_cols_wrap(args, None))?;
+
+    Ok(layout
+        .into_rendered(args.keep)
+        .into_iter()
+        .map(|line| line + "\n")
+        .collect())
+}
+
+/// Fails if the options of a request have one the server can't honour, see [`UNSUPPORTED`].
+fn check_options(matches: &ArgMatches) -> Result<(), String> {
+    let is_given = |id: &str| {
+        matches.try_contains_id(id).is_ok()
+            && matches.value_source(id) == Some(ValueSource::CommandLine)
+    };
+    match Args::command()
+        .get_arguments()
+        .find(|arg| is_given(arg.get_id().as_str()) && UNSUPPORTED.contains(&arg.get_id().as_str()))
+    {
+        Some(arg) => {
+            // the files are positional
+            let name = match (arg.get_long(), arg.get_value_names()) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some([name, ..])) => name.to_string(),
+                (None, _) => arg.get_id().to_string(),
+            };
+            Err(format!(
+                "{name} isn't supported in the options of a request"
+            ))
+        }
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Display;
+
+use align_text::{Layout, Options};
+
+/// Numbers about a run, to explain the output.
+#[derive(Debug)]
+pub struct Stats {
+    /// The terminal's width, if it was used.
+    pub terminal_width: Option<usize>,
+    /// The width of the input text (after trimming if enabled).
+    pub text_width: usize,
+    /// The final number of columns.
+    pub columns: usize,
+    pub input_lines: usize,
+    pub output_lines: usize,
+    /// Number of input lines which were split by wrapping.
+    pub wrapped_lines: usize,
+    /// Number of input lines which don't come out as they went in.
+    pub modified_lines: usize,
+}
+
+impl Stats {
+    pub fn new(
+        input: &[String],
+        aligned: &[String],
+        layout: &Layout,
+        trim: bool,
+        measuring: &Options,
+    ) -> Stats {
+        let text_width = input
+            .iter()
+            .map(|line| measuring.width_of(if trim { line.trim() } else { line }))
+            .max()
+            .unwrap_or(0);
+
+        let wrapped_lines = outputs(input, aligned, layout)
+            .iter()
+            .filter(|output| output.len() > 1)
+            .count();
+        let modified_lines = changed_lines(input, aligned, layout).len();
+
+        Stats {
+            terminal_width: None,
+            text_width,
+            columns: layout.columns,
+            input_lines: input.len(),
+            output_lines: aligned.len(),
+            wrapped_lines,
+            modified_lines,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.terminal_width {
+            Some(width) => writeln!(f, "terminal width: {width}")?,
+            None => writeln!(f, "terminal width: not used")?,
+        }
+        writeln!(f, "text width:     {}", self.text_width)?;
+        writeln!(f, "columns:        {}", self.columns)?;
+        writeln!(f, "input lines:    {}", self.input_lines)?;
+        writeln!(f, "output lines:   {}", self.output_lines)?;
+        writeln!(f, "wrapped lines:  {}", self.wrapped_lines)?;
+        write!(f, "modified lines: {}", self.modified_lines)
+    }
+}
+
+/// The output lines of each input line.
+fn outputs<'a>(input: &[String], aligned: &'a [String], layout: &Layout) -> Vec<Vec<&'a String>> {
+    let mut outputs = vec![vec![]; input.len()];
+    for (line, text) in layout.lines.iter().zip(aligned) {
+        if let Some(output) = outputs.get_mut(line.origin) {
+            output.push(text);
+        }
+    }
+    outputs
+}
+
+/// The indices of the input lines which don't come out as they went in.
+pub fn changed_lines(input: &[String], aligned: &[String], layout: &Layout) -> Vec<usize> {
+    input
+        .iter()
+        .zip(outputs(input, aligned, layout))
+        .enumerate()
+        .filter(|(_, (line, output))| output.as_slice() != [*line])
+        .map(|(index, _)| index)
+        .collect()
+}
@@ -0,0 +1,404 @@
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, RandomState};
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use align_text::{normalize_separators, Bias, Error, LineLayout, Where};
+use clap::{parser::ValueSource, ArgMatches, CommandFactory};
+use flate2::read::MultiGzDecoder;
+
+use crate::failure::Failure;
+use crate::progress::Progress;
+use crate::{decode, hint_if_typed, measuring, snapped, Args};
+
+/// The options of --stream.
+#[derive(clap::Args, Debug, Clone)]
+pub struct StreamArgs {
+    /// Align huge inputs without holding them in memory: stdin is spilled to a temporary file while it's measured,
+    /// then aligned line by line from there. Only the alignments, columns, trimming, and spaces options apply.
+    #[arg(id = "stream", long = "stream", action)]
+    pub enabled: bool,
+
+    /// With --stream and a fixed number of columns, align the lines in chunks on N threads (0 for one per CPU),
+    /// writing them in order as they're read. Only when the alignments inside and outside the block are the same,
+    /// and the bias isn't alternate, the others needing the text's width first.
+    #[arg(long, value_name = "N", requires = "stream")]
+    pub jobs: Option<usize>,
+}
+
+/// The file stdin is spilled to, removed when it's dropped, or when the process is interrupted.
+struct Spill {
+    path: PathBuf,
+}
+
+impl Spill {
+    /// Creates the file in the temporary directory, with an unpredictable name and only readable by the user,
+    /// so that another file (or a link to one) can't be put in its place beforehand, nor be removed after.
+    fn create() -> Result<(Spill, File), Failure> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut attempts = 0;
+        loop {
+            let random = RandomState::new().hash_one((SystemTime::now(), attempts));
+            let path = std::env::temp_dir()
+                .join(format!("align-{}-{random:016x}.spill", std::process::id()));
+            match options.open(&path) {
+                Ok(file) => {
+                    let spill = Spill { path };
+                    #[cfg(unix)]
+                    crate::remove_on_signal(&spill.path).map_err(Failure::Io)?;
+                    return Ok((spill, file));
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists && attempts < 10 => attempts += 1,
+                Err(e) => {
+                    return Err(Failure::Io(format!(
+                        "couldn't spill stdin to {}: {e}",
+                        path.display()
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// The options which apply when streaming, the others failing instead of being ignored.
+const SUPPORTED: [&str; 19] = [
+    "stream",
+    "align",
+    "outer",
+    "inner",
+    "columns",
+    "snap",
+    "trim",
+    "keep",
+    "bias",
+    "mirror",
+    "line_separators",
+    "strict",
+    "gzip",
+    "progress",
+    "jobs",
+    "emoji_width",
+    "bytes",
+    "stdin_filepath",
+    "log_level",
+];
+
+/// Fails if an option which doesn't apply when streaming was given on the command line,
+/// or set by the defaults (`defaulted`, from the config files and the `ALIGN_*` variables), see [`SUPPORTED`].
+pub fn check_options(given: &ArgMatches, defaulted: &[&str]) -> Result<(), Failure> {
+    if let Some(id) = defaulted.iter().find(|id| !SUPPORTED.contains(id)) {
+        return Err(Failure::Usage(format!(
+            "the default '{id}' (from a config file or ALIGN_OPTS) isn't supported with --stream"
+        )));
+    }
+
+    let is_given = |id: &str| {
+        given.try_contains_id(id).is_ok()
+            && given.value_source(id) == Some(ValueSource::CommandLine)
+    };
+    match Args::command()
+        .get_arguments()
+        .find(|arg| is_given(arg.get_id().as_str()) && !SUPPORTED.contains(&arg.get_id().as_str()))
+    {
+        Some(arg) => {
+            // the files are positional
+            let name = match (arg.get_long(), arg.get_value_names()) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some([name, ..])) => name.to_string(),
+                (None, _) => arg.get_id().to_string(),
+            };
+            Err(Failure::Usage(format!(
+                "{name} isn't supported with --stream"
+            )))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Aligns stdin without holding it in memory: it's copied to a temporary file while its width is measured,
+/// then the lines are aligned one by one while reading them back from there.
+/// Only the alignments, the number of columns, and the trimming apply, the lines being aligned independently,
+/// see [`check_options()`].
+pub fn stream(args: &Args, cols_wrap: Option<(usize, bool)>) -> Result<(), Failure> {
+    // each line can be placed in the columns without knowing the text's width
+    if let (Some(jobs), Some((columns, _))) = (args.stream.jobs, cols_wrap) {
+        if args.outer == args.inner && args.bias != Bias::Alternate {
+            return stream_parallel(args, columns, jobs);
+        }
+    }
+
+    let (spill, file) = Spill::create()?;
+    let spill_error = |e: std::io::Error| {
+        Failure::Io(format!(
+            "couldn't spill stdin to {}: {e}",
+            spill.path.display()
+        ))
+    };
+
+    hint_if_typed();
+    // measure the text while spilling it
+    let input: Box<dyn Read> = if args.gzip {
+        Box::new(MultiGzDecoder::new(stdin().lock()))
+    } else {
+        Box::new(stdin().lock())
+    };
+    let mut file = BufWriter::new(file);
+    let mut progress = Progress::new("read", args.progress, None);
+    let measuring = measuring(args);
+    let mut text_width = 0;
+    // the index and content of the widest line, to show it if it doesn't fit
+    let mut widest = (0, String::new());
+    for_each_line(input, args, &mut progress, |index, text, bytes| {
+        file.write_all(bytes).map_err(spill_error)?;
+        let width = measuring.width_of(visible(text, args));
+        if width > text_width {
+            text_width = width;
+            widest = (index, text.to_string());
+        }
+        Ok(())
+    })?;
+    file.flush().map_err(spill_error)?;
+    drop(file);
+    progress.finish();
+
+    let columns = match cols_wrap {
+        Some((num, _)) if num < text_width => {
+            let e = Error::InsufficientColumns {
+                required: text_width,
+                available: num,
+                line_index: widest.0,
+            };
+            return Err(Failure::Align(format!("{e}:\n{}", widest.1)));
+        }
+        Some((num, _)) => num,
+        None => snapped(text_width, args),
+    };
+
+    // the lines are aligned within the text's width, then the text within the columns
+    let mut outer = LineLayout {
+        text: String::new(),
+        origin: 0,
+        range: 0..0,
+        before: 0,
+        after: columns - text_width,
+    };
+    outer.realign(&args.outer, args.bias);
+    let mut odd_lines = 0;
+
+    let file = File::open(&spill.path).map_err(spill_error)?;
+    let mut out = BufWriter::new(stdout().lock());
+    let mut progress = Progress::new("written", args.progress, None);
+    for_each_line(file, args, &mut progress, |index, text, _| {
+        let text = visible(text, args);
+        let line = place(
+            text,
+            measuring.width_of(text),
+            index,
+            text_width,
+            &outer,
+            args,
+            &mut odd_lines,
+        );
+        writeln!(out, "{line}").map_err(|e| Failure::Io(e.to_string()))
+    })?;
+    out.flush().map_err(|e| Failure::Io(e.to_string()))?;
+    progress.finish();
+
+    Ok(())
+}
+
+/// The number of lines aligned together by a thread with --jobs.
+const CHUNK_LINES: usize = 1 << 14;
+
+/// Aligns stdin within the columns in chunks of lines, on `jobs` threads (one per CPU if 0), and writes them in order.
+/// The lines are placed directly in the columns, so the text doesn't need to be read twice.
+fn stream_parallel(args: &Args, columns: usize, jobs: usize) -> Result<(), Failure> {
+    let jobs = match jobs {
+        0 => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        jobs => jobs,
+    };
+
+    hint_if_typed();
+    let input: Box<dyn Read> = if args.gzip {
+        Box::new(MultiGzDecoder::new(stdin().lock()))
+    } else {
+        Box::new(stdin().lock())
+    };
+    let mut out = BufWriter::new(stdout().lock());
+    let mut progress = Progress::new("read", args.progress, None);
+
+    let mut batch: Vec<Vec<(usize, String)>> = vec![vec![]];
+    let mut write_batch = |batch: &mut Vec<Vec<(usize, String)>>| -> Result<(), Failure> {
+        let chunks = std::mem::replace(batch, vec![vec![]]);
+        let aligned = std::thread::scope(|scope| {
+            let handles = chunks
+                .iter()
+                .map(|chunk| scope.spawn(|| align_chunk(chunk, columns, args)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("aligning a chunk doesn't panic"))
+                .collect::<Result<Vec<String>, Failure>>()
+        })?;
+        for chunk in aligned {
+            out.write_all(chunk.as_bytes())
+                .map_err(|e| Failure::Io(e.to_string()))?;
+        }
+        Ok(())
+    };
+
+    for_each_line(input, args, &mut progress, |index, text, _| {
+        let chunk = batch.last_mut().expect("there's always a chunk");
+        chunk.push((index, text.to_string()));
+        if chunk.len() == CHUNK_LINES {
+            if batch.len() == jobs {
+                write_batch(&mut batch)?;
+            } else {
+                batch.push(vec![]);
+            }
+        }
+        Ok(())
+    })?;
+    write_batch(&mut batch)?;
+    progress.finish();
+
+    out.flush().map_err(|e| Failure::Io(e.to_string()))
+}
+
+/// The aligned lines of a chunk, each one followed by a line feed.
+fn align_chunk(chunk: &[(usize, String)], columns: usize, args: &Args) -> Result<String, Failure> {
+    let outer = LineLayout {
+        text: String::new(),
+        origin: 0,
+        range: 0..0,
+        before: 0,
+        after: 0,
+    };
+    // the bias doesn't alternate
+    let mut odd_lines = 0;
+
+    let measuring = measuring(args);
+    let mut aligned = String::new();
+    for (index, line) in chunk {
+        let text = visible(line, args);
+        let width = measuring.width_of(text);
+        if width > columns {
+            let e = Error::InsufficientColumns {
+                required: width,
+                available: columns,
+                line_index: *index,
+            };
+            return Err(Failure::Align(format!("{e}:\n{line}")));
+        }
+        aligned.push_str(&place(
+            text,
+            width,
+            *index,
+            columns,
+            &outer,
+            args,
+            &mut odd_lines,
+        ));
+        aligned.push('\n');
+    }
+    Ok(aligned)
+}
+
+/// Aligns a line `width` wide within the text's width, then within the columns, with the spaces of `outer` around the text.
+fn place(
+    text: &str,
+    width: usize,
+    index: usize,
+    text_width: usize,
+    outer: &LineLayout,
+    args: &Args,
+    odd_lines: &mut usize,
+) -> String {
+    let mut line = LineLayout {
+        text: text.to_string(),
+        origin: index,
+        range: 0..text.len(),
+        before: 0,
+        after: text_width - width,
+    };
+    if args.outer == Where::Center && args.inner == Where::Center {
+        // center completely
+        line.after = text_width + outer.before + outer.after - width;
+        let bias = args.bias.for_line(line.after, odd_lines);
+        line.realign(&Where::Center, bias);
+    } else {
+        let bias = args.bias.for_line(line.after, odd_lines);
+        line.realign(&args.inner, bias);
+        line.before += outer.before;
+        line.after += outer.after;
+        if !args.keep {
+            // like Layout::trim_end()
+            line.text.truncate(line.text.trim_end().len());
+            line.after = 0;
+            if line.text.is_empty() {
+                line.before = 0;
+            }
+        }
+    }
+    line.into_rendered(args.keep)
+}
+
+/// Calls `f` with the index, the text, and the bytes (with the line ending) of each line, one at a time.
+/// The text is split at the line separators inside it according to --line-separators.
+fn for_each_line(
+    input: impl Read,
+    args: &Args,
+    progress: &mut Progress,
+    mut f: impl FnMut(usize, &str, &[u8]) -> Result<(), Failure>,
+) -> Result<(), Failure> {
+    let mut input = BufReader::new(input);
+    let mut bytes = vec![];
+    let mut index = 0;
+
+    loop {
+        bytes.clear();
+        let read = input
+            .read_until(b'\n', &mut bytes)
+            .map_err(|e| Failure::Io(e.to_string()))?;
+        if read == 0 {
+            return Ok(());
+        }
+        progress.tick(read);
+
+        let mut line = bytes.as_slice();
+        if let Some(rest) = line.strip_suffix(b"\n") {
+            line = rest.strip_suffix(b"\r").unwrap_or(rest);
+        }
+        let text = decode(line.to_vec(), args.strict, format!("line {}", index + 1))
+            .map_err(Failure::Io)?;
+        let texts = normalize_separators(&[text], args.line_separators.into())
+            .map_err(|e| Failure::Align(e.map_line_index(|_| index).to_string()))?;
+
+        for (part, text) in texts.iter().enumerate() {
+            // the bytes are only passed once per line
+            f(index, text, if part == 0 { &bytes } else { &[] })?;
+            index += 1;
+        }
+    }
+}
+
+/// The text of the line which is aligned.
+fn visible<'a>(text: &'a str, args: &Args) -> &'a str {
+    if args.trim {
+        text.trim()
+    } else {
+        text
+    }
+}
@@ -0,0 +1,331 @@
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+use align_text::{Bias, LineFormat, RecordLayout, Where};
+use clap::{ArgGroup, Parser};
+
+use crate::{Args, Preset, Splitter, WrapMode};
+
+/// The options shared by the subcommands: where to align, within how many columns, and what to read.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Common {
+    /// Where to align the block of text.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        default_value_t,
+        ignore_case = true,
+        conflicts_with = "align"
+    )]
+    outer: Where,
+
+    /// Where to align text inside the block.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        default_value_t,
+        ignore_case = true,
+        conflicts_with = "align"
+    )]
+    inner: Where,
+
+    /// Shorthand for specifiying both.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        ignore_case = true,
+        conflicts_with = "outer",
+        conflicts_with = "inner"
+    )]
+    align: Option<Where>,
+
+    /// Number of columns. Takes text's width if 0, terminal's width if unspecified.
+    #[arg(short, long)]
+    columns: Option<usize>,
+
+    /// Trim the spaces around the lines before aligning.
+    #[arg(short, long, action)]
+    trim: bool,
+
+    /// Keep the spaces on the right in output.
+    #[arg(short, long, action)]
+    keep: bool,
+
+    /// Offset if line can't be centered perfectly
+    #[arg(value_enum, short, long, default_value_t, ignore_case = true)]
+    bias: Bias,
+
+    /// Read the text from the FILEs instead of stdin, aligning each of them separately, one after another.
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+}
+
+impl Common {
+    /// The args of `align` with these options, and the defaults for the others.
+    fn into_args(self) -> Args {
+        let mut args = Args::parse_from(["align"]);
+        args.outer = self.outer;
+        args.inner = self.inner;
+        args.align = self.align;
+        args.columns = self.columns;
+        args.trim = self.trim;
+        args.keep = self.keep;
+        args.bias = self.bias;
+        args.files = self.files;
+        args
+    }
+}
+
+/// The options lining up the fields of the lines, of `align` and `align table`.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct TableArgs {
+    /// Format the fields of each line before aligning (e.g. '{:<20} {:^10} {:>8}').
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<LineFormat>,
+
+    /// Align the fields without an alignment in --format left, instead of right for the columns of numbers.
+    #[arg(long, action, requires = "format")]
+    pub no_auto_numeric: bool,
+
+    /// Take ',' as the decimal separator of the numbers in --format, and '.' as a thousands separator.
+    #[arg(long, action, requires = "format")]
+    pub decimal_comma: bool,
+
+    /// Split each line into fixed-width fields and align each within its field (e.g. '10:left,8:right,20:left:ellipsis').
+    /// A field is WIDTH[:ALIGN[:TRUNCATE]], TRUNCATE being 'cut' (default), 'ellipsis', or 'error'.
+    #[arg(long, value_name = "FIELDS", conflicts_with = "format")]
+    pub fields: Option<RecordLayout>,
+
+    /// Split the lines into --fields at CHAR, instead of at the fields' widths.
+    #[arg(long, value_name = "CHAR", requires = "fields")]
+    pub field_delimiter: Option<char>,
+}
+
+/// The options flowing the lines into columns side by side, of `align` (`align grid` has its own).
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct FlowArgs {
+    /// Flow the lines into N columns side by side, like a newspaper.
+    #[arg(
+        id = "flow",
+        long = "flow",
+        value_name = "N",
+        conflicts_with_all = ["pipeline", "prefixed"]
+    )]
+    pub count: Option<NonZeroUsize>,
+
+    /// Number of lines in each column with --flow, spreads the lines evenly if unspecified.
+    #[arg(
+        id = "flow_rows",
+        long = "flow-rows",
+        value_name = "ROWS",
+        requires = "flow"
+    )]
+    pub rows: Option<usize>,
+
+    /// Number of spaces between the columns with --flow.
+    #[arg(long, value_name = "SPACES", default_value_t = 2, requires = "flow")]
+    pub gutter: usize,
+}
+
+/// `align wrap`: wraps the lines which are too long to fit in the columns.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Wrap {
+    #[command(flatten)]
+    common: Common,
+
+    /// Split the lines anywhere, or with 'overflow-only' between words, only breaking the words which are too long.
+    #[arg(value_enum, long = "mode", id = "wrap", default_value = "anywhere")]
+    mode: WrapMode,
+
+    /// How to split the words which are wider than the columns with --mode=overflow-only.
+    #[arg(value_enum, long, default_value_t, value_name = "SPLITTER")]
+    word_splitter: Splitter,
+
+    /// Join the lines of each paragraph (separated by blank lines) before wrapping them again.
+    #[arg(long, action)]
+    reflow: bool,
+
+    /// Start the remainders of wrapped lines with PREFIX, counted in the number of columns.
+    #[arg(long, value_name = "PREFIX")]
+    continuation: Option<String>,
+
+    /// End the wrapped lines which continue on the next line with MARKER (e.g. '↩'), counted in the number of columns.
+    #[arg(long, value_name = "MARKER")]
+    wrap_marker: Option<String>,
+
+    /// Where to align the remainders of wrapped lines, like the other lines if unspecified.
+    #[arg(value_enum, long, ignore_case = true, value_name = "WHERE")]
+    remainder: Option<Where>,
+
+    /// Where to align the last line of each paragraph, like the other lines if unspecified.
+    #[arg(value_enum, long, ignore_case = true, value_name = "WHERE")]
+    last_line: Option<Where>,
+
+    /// Treat the text as markdown: don't break code spans, links, and emphasis,
+    /// and leave the fenced code blocks as they are.
+    #[arg(long, action)]
+    markdown: bool,
+
+    /// Don't break the URLs and file paths.
+    #[arg(long, action)]
+    keep_urls: bool,
+
+    /// Don't break the tokens matching this regular expression (can be repeated).
+    #[arg(long, value_name = "REGEX")]
+    unbreakable: Vec<String>,
+}
+
+impl Wrap {
+    pub fn into_args(self) -> Args {
+        let mut args = self.common.into_args();
+        args.wrap = Some(self.mode);
+        args.word_splitter = self.word_splitter;
+        args.reflow = self.reflow;
+        args.continuation = self.continuation;
+        args.wrap_marker = self.wrap_marker;
+        args.remainder = self.remainder;
+        args.last_line = self.last_line;
+        args.markdown = self.markdown;
+        args.keep_urls = self.keep_urls;
+        args.unbreakable = self.unbreakable;
+        args
+    }
+}
+
+/// `align table`: lines up the fields of the lines, then aligns them as a block.
+#[derive(clap::Args, Debug, Clone)]
+#[command(group(ArgGroup::new("layout").required(true).args(["format", "fields", "on_operator", "preset"])))]
+pub struct Table {
+    #[command(flatten)]
+    common: Common,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    /// Align the operators of consecutive lines to the same column,
+    /// among those given (comma-separated), or '=', ':=', '=>', and ':'.
+    #[arg(
+        long,
+        value_name = "OPERATORS",
+        value_delimiter = ',',
+        num_args = 0..=1,
+        default_missing_value = ":=,=>,=,:"
+    )]
+    on_operator: Option<Vec<String>>,
+
+    /// Use the options for a kind of file: align the values of a dotenv file on '=', or of a YAML file on ':'.
+    #[arg(value_enum, long, ignore_case = true)]
+    preset: Option<Preset>,
+}
+
+impl Table {
+    pub fn into_args(self) -> Args {
+        let mut args = self.common.into_args();
+        args.table = self.table;
+        args.on_operator = self.on_operator;
+        args.preset = self.preset;
+        args
+    }
+}
+
+/// `align grid`: flows the lines into columns side by side, like a newspaper.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Grid {
+    /// Number of columns of lines.
+    #[arg(value_name = "N")]
+    count: NonZeroUsize,
+
+    #[command(flatten)]
+    common: Common,
+
+    /// Number of lines in each column, spreads the lines evenly if unspecified.
+    #[arg(long)]
+    rows: Option<usize>,
+
+    /// Number of spaces between the columns.
+    #[arg(long, value_name = "SPACES", default_value_t = 2)]
+    gutter: usize,
+}
+
+impl Grid {
+    pub fn into_args(self) -> Args {
+        let mut args = self.common.into_args();
+        args.flow = FlowArgs {
+            count: Some(self.count),
+            rows: self.rows,
+            gutter: self.gutter,
+        };
+        args
+    }
+}
+
+/// `align check`: tells if the text is already aligned.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Check {
+    #[command(flatten)]
+    common: Common,
+
+    /// Whether the lines should be wrapped to fit in the columns: anywhere, or between words with 'overflow-only'.
+    #[arg(
+        value_enum,
+        short,
+        long,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "anywhere"
+    )]
+    wrap: Option<WrapMode>,
+}
+
+impl Check {
+    pub fn into_args(self) -> Args {
+        let mut args = self.common.into_args();
+        args.wrap = self.wrap;
+        args.check = true;
+        args
+    }
+}
+
+/// `align fmt`: reflows the paragraphs like fmt(1), with its options, so it can replace it in scripts.
+/// The text is left-aligned, the paragraphs' indentation is kept, and the words are never broken.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Fmt {
+    /// Maximum line width.
+    #[arg(short, long, default_value_t = 75)]
+    width: usize,
+
+    /// Reformat only the lines beginning with PREFIX (after the indentation), reattaching it to the reformatted lines.
+    #[arg(short, long, value_name = "PREFIX")]
+    prefix: Option<String>,
+
+    /// Uniform spacing: one space between words, two after sentences (followed by two spaces or a line break).
+    #[arg(short, long, action)]
+    uniform_spacing: bool,
+
+    /// Split the long lines, but don't refill them.
+    #[arg(short, long, action)]
+    split_only: bool,
+
+    /// Read the text from the FILEs instead of stdin, reflowing each of them separately, one after another.
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+}
+
+impl Fmt {
+    pub fn into_args(self) -> Args {
+        let mut args = Args::parse_from(["align"]);
+        args.columns = Some(self.width);
+        args.wrap = Some(WrapMode::OverflowOnly);
+        args.word_splitter = Splitter::Never;
+        args.reflow = !self.split_only;
+        // an empty marker keeps the indentation of each paragraph
+        args.comment = Some(self.prefix.unwrap_or_default());
+        args.uniform_spacing = self.uniform_spacing;
+        args.files = self.files;
+        args
+    }
+}
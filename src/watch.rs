@@ -0,0 +1,58 @@
+use std::io::{stdout, Write};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+use crossterm::event::{self, Event};
+
+/// How often the file's modification time is checked, between the terminal's events.
+const INTERVAL: Duration = Duration::from_millis(200);
+
+/// Clears the terminal and moves the cursor to the top left.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Redraws the output of `draw` whenever the file at `path` is modified or the terminal is resized, until interrupted.
+/// Errors returned by `draw` are displayed in place of the output, since the next change may fix them.
+///
+/// The resizes are redrawn as soon as the terminal reports them (SIGWINCH), while the file is checked every [`INTERVAL`].
+pub fn watch(path: &Path, mut draw: impl FnMut() -> Result<String, String>) -> Result<(), String> {
+    let mut last_modified = modified(path);
+    let mut changed = true;
+
+    loop {
+        if changed {
+            let output = draw().unwrap_or_else(|e| format!("error: {e}\n"));
+
+            let mut out = stdout().lock();
+            write!(out, "{CLEAR}{output}").map_err(|e| e.to_string())?;
+            out.flush().map_err(|e| e.to_string())?;
+        }
+
+        changed = resized(INTERVAL);
+        let current = modified(path);
+        if current != last_modified {
+            last_modified = current;
+            changed = true;
+        }
+    }
+}
+
+/// Waits for the terminal to be resized, at most `timeout`, and tells if it was.
+/// Without a terminal to read the events of, it only waits.
+fn resized(timeout: Duration) -> bool {
+    match event::poll(timeout) {
+        Ok(true) => matches!(event::read(), Ok(Event::Resize(..))),
+        Ok(false) => false,
+        Err(_) => {
+            sleep(timeout);
+            false
+        }
+    }
+}
+
+/// The last modification time of the file, `None` if it's missing (e.g. while it's being replaced).
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}